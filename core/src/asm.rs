@@ -0,0 +1,451 @@
+use crate::cpu;
+use crate::ram;
+
+/// CHIP-8 opcodes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Asm {
+    /// Jump to machine code routine at address
+    SYS(ram::Addr),
+
+    /// Clear screen
+    CLS,
+
+    /// Return from subroutine
+    RET,
+
+    /// Jump to address
+    JP(ram::Addr),
+
+    /// Call subroutine at address
+    CALL(ram::Addr),
+
+    /// Skip next instruction if register contents equal constant
+    SEC(cpu::Reg, u8),
+
+    /// Skip next instruction if register contents do not equal constant
+    SNEC(cpu::Reg, u8),
+
+    /// Skip next instruction if register contents are equal
+    SER(cpu::Reg, cpu::Reg),
+
+    /// Load constant into register
+    LDC(cpu::Reg, u8),
+
+    /// Destructive constant addition
+    ADDC(cpu::Reg, u8),
+
+    /// Load register contents into register
+    LDR(cpu::Reg, cpu::Reg),
+
+    /// Destructive register bitwise OR
+    OR(cpu::Reg, cpu::Reg),
+
+    /// Destructive register bitwise AND
+    AND(cpu::Reg, cpu::Reg),
+
+    /// Destructive register bitwise XOR
+    XOR(cpu::Reg, cpu::Reg),
+
+    /// Destructive register addition
+    ADDR(cpu::Reg, cpu::Reg),
+
+    /// Destructive left-to-right register subtraction
+    SUB(cpu::Reg, cpu::Reg),
+
+    /// Shift right by one
+    SHR(cpu::Reg, cpu::Reg),
+
+    /// Destructive right-to-left register subtraction
+    SUBN(cpu::Reg, cpu::Reg),
+
+    /// Shift left by one
+    SHL(cpu::Reg, cpu::Reg),
+
+    /// Skip next instruction if register contents are not equal
+    SNER(cpu::Reg, cpu::Reg),
+
+    /// Load address into register I
+    LDI(ram::Addr),
+
+    /// Jump to address plus offset V0
+    JO(ram::Addr),
+
+    /// Generate random number and bitwise AND with constant
+    RND(cpu::Reg, u8),
+
+    /// Display n-byte sprite
+    DRW(cpu::Reg, cpu::Reg, u8),
+
+    /// Skip if key with value is pressed
+    SKP(cpu::Reg),
+
+    /// Skip if key with value is not pressed
+    SKNP(cpu::Reg),
+
+    /// Load value of delay timer into register
+    LDTR(cpu::Reg),
+
+    /// Load value of key press
+    LDK(cpu::Reg),
+
+    /// Load value of register into delay timer
+    LDRT(cpu::Reg),
+
+    /// Load value of register into sound timer
+    LDRS(cpu::Reg),
+
+    /// Destructive register addition into I
+    ADDI(cpu::Reg),
+
+    /// Set I to location of sprite for digit
+    LDS(cpu::Reg),
+
+    /// Store BCD representation of register in memory locations I, I + 1, I + 2
+    LDB(cpu::Reg),
+
+    /// Write registers V0 through Vx in memory starting at location I
+    WR(u8),
+
+    /// Read registers V0 through Vx from memory starting at location I
+    RD(u8),
+}
+
+impl Asm {
+    /// Decode the big-endian instruction word `hi`, `lo`, or `None` if it is invalid.
+    pub fn parse(hi: u8, lo: u8) -> Option<Self> {
+        let op = ((hi as u16) << 8) | (lo as u16);
+        match (hi >> 4, hi & 0xF, lo >> 4, lo & 0xF) {
+        | (0x0, 0x0, 0xE, 0x0) => Some(Asm::CLS),
+        | (0x0, 0x0, 0xE, 0xE) => Some(Asm::RET),
+        | (0x0,   _,   _,   _) => Some(Asm::SYS(op.into())),
+        | (0x1,   _,   _,   _) => Some(Asm::JP(op.into())),
+        | (0x2,   _,   _,   _) => Some(Asm::CALL(op.into())),
+        | (0x3,   x,   _,   _) => Some(Asm::SEC(x.into(), op as u8)),
+        | (0x4,   x,   _,   _) => Some(Asm::SNEC(x.into(), op as u8)),
+        | (0x5,   x,   y, 0x0) => Some(Asm::SER(x.into(), y.into())),
+        | (0x6,   x,   _,   _) => Some(Asm::LDC(x.into(), op as u8)),
+        | (0x7,   x,   _,   _) => Some(Asm::ADDC(x.into(), op as u8)),
+        | (0x8,   x,   y, 0x0) => Some(Asm::LDR(x.into(), y.into())),
+        | (0x8,   x,   y, 0x1) => Some(Asm::OR(x.into(), y.into())),
+        | (0x8,   x,   y, 0x2) => Some(Asm::AND(x.into(), y.into())),
+        | (0x8,   x,   y, 0x3) => Some(Asm::XOR(x.into(), y.into())),
+        | (0x8,   x,   y, 0x4) => Some(Asm::ADDR(x.into(), y.into())),
+        | (0x8,   x,   y, 0x5) => Some(Asm::SUB(x.into(), y.into())),
+        | (0x8,   x,   y, 0x6) => Some(Asm::SHR(x.into(), y.into())),
+        | (0x8,   x,   y, 0x7) => Some(Asm::SUBN(x.into(), y.into())),
+        | (0x8,   x,   y, 0xE) => Some(Asm::SHL(x.into(), y.into())),
+        | (0x9,   x,   y, 0x0) => Some(Asm::SNER(x.into(), y.into())),
+        | (0xA,   _,   _,   _) => Some(Asm::LDI(op.into())),
+        | (0xB,   _,   _,   _) => Some(Asm::JO(op.into())),
+        | (0xC,   x,   _,   _) => Some(Asm::RND(x.into(), op as u8)),
+        | (0xD,   x,   y,   n) => Some(Asm::DRW(x.into(), y.into(), n)),
+        | (0xE,   x, 0x9, 0xE) => Some(Asm::SKP(x.into())),
+        | (0xE,   x, 0xA, 0x1) => Some(Asm::SKNP(x.into())),
+        | (0xF,   x, 0x0, 0x7) => Some(Asm::LDTR(x.into())),
+        | (0xF,   x, 0x0, 0xA) => Some(Asm::LDK(x.into())),
+        | (0xF,   x, 0x1, 0x5) => Some(Asm::LDRT(x.into())),
+        | (0xF,   x, 0x1, 0x8) => Some(Asm::LDRS(x.into())),
+        | (0xF,   x, 0x1, 0xE) => Some(Asm::ADDI(x.into())),
+        | (0xF,   x, 0x2, 0x9) => Some(Asm::LDS(x.into())),
+        | (0xF,   x, 0x3, 0x3) => Some(Asm::LDB(x.into())),
+        | (0xF,   x, 0x5, 0x5) => Some(Asm::WR(x)),
+        | (0xF,   x, 0x6, 0x5) => Some(Asm::RD(x)),
+        | _ => None,
+        }
+    }
+
+    /// Encode as an instruction word.
+    ///
+    /// `SYS 0x0E0` and `SYS 0x0EE` encode the same words as `CLS` and `RET`,
+    /// and decode as those instead.
+    pub fn encode(&self) -> u16 {
+        let x = |reg: cpu::Reg| (u8::from(reg) as u16) << 8;
+        let y = |reg: cpu::Reg| (u8::from(reg) as u16) << 4;
+        let addr = |addr: ram::Addr| u16::from(addr);
+        match *self {
+        | Asm::SYS(a)       => addr(a),
+        | Asm::CLS          => 0x00E0,
+        | Asm::RET          => 0x00EE,
+        | Asm::JP(a)        => 0x1000 | addr(a),
+        | Asm::CALL(a)      => 0x2000 | addr(a),
+        | Asm::SEC(vx, kk)  => 0x3000 | x(vx) | kk as u16,
+        | Asm::SNEC(vx, kk) => 0x4000 | x(vx) | kk as u16,
+        | Asm::SER(vx, vy)  => 0x5000 | x(vx) | y(vy),
+        | Asm::LDC(vx, kk)  => 0x6000 | x(vx) | kk as u16,
+        | Asm::ADDC(vx, kk) => 0x7000 | x(vx) | kk as u16,
+        | Asm::LDR(vx, vy)  => 0x8000 | x(vx) | y(vy),
+        | Asm::OR(vx, vy)   => 0x8001 | x(vx) | y(vy),
+        | Asm::AND(vx, vy)  => 0x8002 | x(vx) | y(vy),
+        | Asm::XOR(vx, vy)  => 0x8003 | x(vx) | y(vy),
+        | Asm::ADDR(vx, vy) => 0x8004 | x(vx) | y(vy),
+        | Asm::SUB(vx, vy)  => 0x8005 | x(vx) | y(vy),
+        | Asm::SHR(vx, vy)  => 0x8006 | x(vx) | y(vy),
+        | Asm::SUBN(vx, vy) => 0x8007 | x(vx) | y(vy),
+        | Asm::SHL(vx, vy)  => 0x800E | x(vx) | y(vy),
+        | Asm::SNER(vx, vy) => 0x9000 | x(vx) | y(vy),
+        | Asm::LDI(a)       => 0xA000 | addr(a),
+        | Asm::JO(a)        => 0xB000 | addr(a),
+        | Asm::RND(vx, kk)  => 0xC000 | x(vx) | kk as u16,
+        | Asm::DRW(vx, vy, n) => 0xD000 | x(vx) | y(vy) | (n & 0xF) as u16,
+        | Asm::SKP(vx)      => 0xE09E | x(vx),
+        | Asm::SKNP(vx)     => 0xE0A1 | x(vx),
+        | Asm::LDTR(vx)     => 0xF007 | x(vx),
+        | Asm::LDK(vx)      => 0xF00A | x(vx),
+        | Asm::LDRT(vx)     => 0xF015 | x(vx),
+        | Asm::LDRS(vx)     => 0xF018 | x(vx),
+        | Asm::ADDI(vx)     => 0xF01E | x(vx),
+        | Asm::LDS(vx)      => 0xF029 | x(vx),
+        | Asm::LDB(vx)      => 0xF033 | x(vx),
+        | Asm::WR(n)        => 0xF055 | ((n & 0xF) as u16) << 8,
+        | Asm::RD(n)        => 0xF065 | ((n & 0xF) as u16) << 8,
+        }
+    }
+
+    /// Name of this opcode's variant, e.g. `ADDC`.
+    pub fn name(&self) -> &'static str {
+        match self {
+        | Asm::SYS(..)  => "SYS",
+        | Asm::CLS      => "CLS",
+        | Asm::RET      => "RET",
+        | Asm::JP(..)   => "JP",
+        | Asm::CALL(..) => "CALL",
+        | Asm::SEC(..)  => "SEC",
+        | Asm::SNEC(..) => "SNEC",
+        | Asm::SER(..)  => "SER",
+        | Asm::LDC(..)  => "LDC",
+        | Asm::ADDC(..) => "ADDC",
+        | Asm::LDR(..)  => "LDR",
+        | Asm::OR(..)   => "OR",
+        | Asm::AND(..)  => "AND",
+        | Asm::XOR(..)  => "XOR",
+        | Asm::ADDR(..) => "ADDR",
+        | Asm::SUB(..)  => "SUB",
+        | Asm::SHR(..)  => "SHR",
+        | Asm::SUBN(..) => "SUBN",
+        | Asm::SHL(..)  => "SHL",
+        | Asm::SNER(..) => "SNER",
+        | Asm::LDI(..)  => "LDI",
+        | Asm::JO(..)   => "JO",
+        | Asm::RND(..)  => "RND",
+        | Asm::DRW(..)  => "DRW",
+        | Asm::SKP(..)  => "SKP",
+        | Asm::SKNP(..) => "SKNP",
+        | Asm::LDTR(..) => "LDTR",
+        | Asm::LDK(..)  => "LDK",
+        | Asm::LDRT(..) => "LDRT",
+        | Asm::LDRS(..) => "LDRS",
+        | Asm::ADDI(..) => "ADDI",
+        | Asm::LDS(..)  => "LDS",
+        | Asm::LDB(..)  => "LDB",
+        | Asm::WR(..)   => "WR",
+        | Asm::RD(..)   => "RD",
+        }
+    }
+}
+
+impl core::fmt::Display for Asm {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+        | Asm::SYS(addr)    => write!(fmt, "SYS {}", addr),
+        | Asm::CLS          => write!(fmt, "CLS"),
+        | Asm::RET          => write!(fmt, "RET"),
+        | Asm::JP(addr)     => write!(fmt, "JP {}", addr),
+        | Asm::CALL(addr)   => write!(fmt, "CALL {}", addr),
+        | Asm::SEC(x, kk)   => write!(fmt, "SE {}, {:#04X}", x, kk),
+        | Asm::SNEC(x, kk)  => write!(fmt, "SNE {}, {:#04X}", x, kk),
+        | Asm::SER(x, y)    => write!(fmt, "SE {}, {}", x, y),
+        | Asm::LDC(x, kk)   => write!(fmt, "LD {}, {:#04X}", x, kk),
+        | Asm::ADDC(x, kk)  => write!(fmt, "ADD {}, {:#04X}", x, kk),
+        | Asm::LDR(x, y)    => write!(fmt, "LD {}, {}", x, y),
+        | Asm::OR(x, y)     => write!(fmt, "OR {}, {}", x, y),
+        | Asm::AND(x, y)    => write!(fmt, "AND {}, {}", x, y),
+        | Asm::XOR(x, y)    => write!(fmt, "XOR {}, {}", x, y),
+        | Asm::ADDR(x, y)   => write!(fmt, "ADD {}, {}", x, y),
+        | Asm::SUB(x, y)    => write!(fmt, "SUB {}, {}", x, y),
+        | Asm::SHR(x, y)    => write!(fmt, "SHR {}, {}", x, y),
+        | Asm::SUBN(x, y)   => write!(fmt, "SUBN {}, {}", x, y),
+        | Asm::SHL(x, y)    => write!(fmt, "SHL {}, {}", x, y),
+        | Asm::SNER(x, y)   => write!(fmt, "SNE {}, {}", x, y),
+        | Asm::LDI(addr)    => write!(fmt, "LD I, {}", addr),
+        | Asm::JO(addr)     => write!(fmt, "JP V0, {}", addr),
+        | Asm::RND(x, kk)   => write!(fmt, "RND {}, {}", x, kk),
+        | Asm::DRW(x, y, k) => write!(fmt, "DRW {}, {}, {}", x, y, k),
+        | Asm::SKP(x)       => write!(fmt, "SKP {}", x),
+        | Asm::SKNP(x)      => write!(fmt, "SKNP {}", x),
+        | Asm::LDTR(x)      => write!(fmt, "LD {}, DT", x),
+        | Asm::LDK(x)       => write!(fmt, "LD {}, K", x),
+        | Asm::LDRT(x)      => write!(fmt, "LD DT, {}", x),
+        | Asm::LDRS(x)      => write!(fmt, "LD ST, {}", x),
+        | Asm::ADDI(x)      => write!(fmt, "ADD I, {}", x),
+        | Asm::LDS(x)       => write!(fmt, "LD F, {}", x),
+        | Asm::LDB(x)       => write!(fmt, "LD B, {}", x),
+        | Asm::WR(x)        => write!(fmt, "LD [I], V{:X}", x),
+        | Asm::RD(x)        => write!(fmt, "LD V{:X}, [I]", x),
+        }
+    }
+}
+
+/// Operand of an assembly instruction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Operand {
+    /// Register V0 through VF
+    Reg(u8),
+
+    /// Decimal or `0x`-prefixed hexadecimal number
+    Num(u16),
+
+    /// Index register `I`
+    I,
+
+    /// Memory at I, `[I]`
+    Mem,
+
+    /// Delay timer `DT`
+    DT,
+
+    /// Sound timer `ST`
+    ST,
+
+    /// Key press `K`
+    K,
+
+    /// Font sprite `F`
+    F,
+
+    /// BCD digits `B`
+    B,
+}
+
+/// Error assembling text into an instruction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AsmError {
+    /// Operand that is not a register, number, or special name
+    Operand,
+
+    /// Mnemonic and operands that do not form an instruction
+    Instruction,
+
+    /// Address beyond 0xFFF
+    Address(u16),
+
+    /// Constant beyond 0xFF
+    Constant(u16),
+}
+
+impl core::fmt::Display for AsmError {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+        | AsmError::Operand => write!(fmt, "Invalid operand"),
+        | AsmError::Instruction => write!(fmt, "Invalid instruction"),
+        | AsmError::Address(a) => write!(fmt, "Address `{:#X}` beyond 0xFFF", a),
+        | AsmError::Constant(b) => write!(fmt, "Constant `{:#X}` beyond 0xFF", b),
+        }
+    }
+}
+
+impl core::error::Error for AsmError {}
+
+impl core::str::FromStr for Operand {
+    type Err = AsmError;
+    fn from_str(operand: &str) -> Result<Self, Self::Err> {
+        let number = |digits: &str, radix| u16::from_str_radix(digits, radix).ok().filter(|_| !digits.is_empty());
+        let is = |name: &str| operand.eq_ignore_ascii_case(name);
+        let parsed = match operand.as_bytes() {
+        | _ if is("I") => Some(Operand::I),
+        | _ if is("[I]") => Some(Operand::Mem),
+        | _ if is("DT") => Some(Operand::DT),
+        | _ if is("ST") => Some(Operand::ST),
+        | _ if is("K") => Some(Operand::K),
+        | _ if is("F") => Some(Operand::F),
+        | _ if is("B") => Some(Operand::B),
+        | [b'V', _] | [b'v', _] => number(&operand[1..], 16).map(|reg| Operand::Reg(reg as u8)),
+        | [b'0', b'X', ..] | [b'0', b'x', ..] => number(&operand[2..], 16).map(Operand::Num),
+        | _ => number(operand, 10).map(Operand::Num),
+        };
+        parsed.ok_or(AsmError::Operand)
+    }
+}
+
+impl core::str::FromStr for Asm {
+    type Err = AsmError;
+
+    /// Assemble one instruction, written as `Display` formats it, e.g. `LD V0, 0x1F`.
+    /// Mnemonics, registers, and hexadecimal digits are case-insensitive.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        use Operand::*;
+
+        let text = text.trim();
+        let (name, rest) = match text.find(char::is_whitespace) {
+        | Some(split) => (&text[..split], text[split..].trim()),
+        | None => (text, ""),
+        };
+
+        // Longest mnemonic is four letters
+        let mut upper = [0; 4];
+        if name.len() > upper.len() {
+            return Err(AsmError::Instruction);
+        }
+        upper[..name.len()].copy_from_slice(name.as_bytes());
+        upper.make_ascii_uppercase();
+        let name = core::str::from_utf8(&upper[..name.len()]).map_err(|_| AsmError::Instruction)?;
+
+        // Most operands is three, for `DRW`
+        let mut operands = [I; 3];
+        let mut count = 0;
+        if !rest.is_empty() {
+            for operand in rest.split(',') {
+                if count == operands.len() {
+                    return Err(AsmError::Instruction);
+                }
+                operands[count] = operand.trim().parse()?;
+                count += 1;
+            }
+        }
+
+        let invalid = || AsmError::Instruction;
+        let addr = |a: u16| if a <= 0xFFF { Ok(ram::Addr::from(a)) } else { Err(AsmError::Address(a)) };
+        let byte = |b: u16| if b <= 0xFF { Ok(b as u8) } else { Err(AsmError::Constant(b)) };
+        let reg = |r: u8| cpu::Reg::from(r);
+
+        match (name, &operands[..count]) {
+        | ("CLS",  [])                     => Ok(Asm::CLS),
+        | ("RET",  [])                     => Ok(Asm::RET),
+        | ("SYS",  [Num(a)])               => Ok(Asm::SYS(addr(*a)?)),
+        | ("JP",   [Num(a)])               => Ok(Asm::JP(addr(*a)?)),
+        | ("JP",   [Reg(0), Num(a)])       => Ok(Asm::JO(addr(*a)?)),
+        | ("CALL", [Num(a)])               => Ok(Asm::CALL(addr(*a)?)),
+        | ("SE",   [Reg(x), Num(kk)])      => Ok(Asm::SEC(reg(*x), byte(*kk)?)),
+        | ("SNE",  [Reg(x), Num(kk)])      => Ok(Asm::SNEC(reg(*x), byte(*kk)?)),
+        | ("SE",   [Reg(x), Reg(y)])       => Ok(Asm::SER(reg(*x), reg(*y))),
+        | ("LD",   [Reg(x), Num(kk)])      => Ok(Asm::LDC(reg(*x), byte(*kk)?)),
+        | ("ADD",  [Reg(x), Num(kk)])      => Ok(Asm::ADDC(reg(*x), byte(*kk)?)),
+        | ("LD",   [Reg(x), Reg(y)])       => Ok(Asm::LDR(reg(*x), reg(*y))),
+        | ("OR",   [Reg(x), Reg(y)])       => Ok(Asm::OR(reg(*x), reg(*y))),
+        | ("AND",  [Reg(x), Reg(y)])       => Ok(Asm::AND(reg(*x), reg(*y))),
+        | ("XOR",  [Reg(x), Reg(y)])       => Ok(Asm::XOR(reg(*x), reg(*y))),
+        | ("ADD",  [Reg(x), Reg(y)])       => Ok(Asm::ADDR(reg(*x), reg(*y))),
+        | ("SUB",  [Reg(x), Reg(y)])       => Ok(Asm::SUB(reg(*x), reg(*y))),
+        | ("SHR",  [Reg(x)])               => Ok(Asm::SHR(reg(*x), reg(*x))),
+        | ("SHR",  [Reg(x), Reg(y)])       => Ok(Asm::SHR(reg(*x), reg(*y))),
+        | ("SUBN", [Reg(x), Reg(y)])       => Ok(Asm::SUBN(reg(*x), reg(*y))),
+        | ("SHL",  [Reg(x)])               => Ok(Asm::SHL(reg(*x), reg(*x))),
+        | ("SHL",  [Reg(x), Reg(y)])       => Ok(Asm::SHL(reg(*x), reg(*y))),
+        | ("SNE",  [Reg(x), Reg(y)])       => Ok(Asm::SNER(reg(*x), reg(*y))),
+        | ("LD",   [I, Num(a)])            => Ok(Asm::LDI(addr(*a)?)),
+        | ("RND",  [Reg(x), Num(kk)])      => Ok(Asm::RND(reg(*x), byte(*kk)?)),
+        | ("DRW",  [Reg(x), Reg(y), Num(n)]) if *n <= 0xF => Ok(Asm::DRW(reg(*x), reg(*y), *n as u8)),
+        | ("SKP",  [Reg(x)])               => Ok(Asm::SKP(reg(*x))),
+        | ("SKNP", [Reg(x)])               => Ok(Asm::SKNP(reg(*x))),
+        | ("LD",   [Reg(x), DT])           => Ok(Asm::LDTR(reg(*x))),
+        | ("LD",   [Reg(x), K])            => Ok(Asm::LDK(reg(*x))),
+        | ("LD",   [DT, Reg(x)])           => Ok(Asm::LDRT(reg(*x))),
+        | ("LD",   [ST, Reg(x)])           => Ok(Asm::LDRS(reg(*x))),
+        | ("ADD",  [I, Reg(x)])            => Ok(Asm::ADDI(reg(*x))),
+        | ("LD",   [F, Reg(x)])            => Ok(Asm::LDS(reg(*x))),
+        | ("LD",   [B, Reg(x)])            => Ok(Asm::LDB(reg(*x))),
+        | ("LD",   [Mem, Reg(x)])          => Ok(Asm::WR(*x)),
+        | ("LD",   [Reg(x), Mem])          => Ok(Asm::RD(*x)),
+        | _ => Err(invalid()),
+        }
+    }
+}
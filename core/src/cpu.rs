@@ -2,6 +2,8 @@ use crate::ram;
 use crate::stack;
 
 /// 4-bit general purpose register
+///
+/// Conversions and arithmetic wrap around past VF, like `ram::Addr` does past 0xFFF.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Reg(u8);
 
@@ -10,24 +12,30 @@ pub const VF: Reg = Reg(0x0F);
 
 impl From<u8> for Reg {
     fn from(reg: u8) -> Self {
-        debug_assert!(reg < 0x10);
-        Reg(reg)
+        Reg(reg & 0xF)
     }
 }
 
-impl std::ops::Add<u8> for Reg {
+impl From<Reg> for u8 {
+    fn from(reg: Reg) -> Self {
+        reg.0
+    }
+}
+
+impl core::ops::Add<u8> for Reg {
     type Output = Reg;
     fn add(self, rhs: u8) -> Self::Output {
-        Reg(self.0 + rhs)
+        Reg::from(self.0.wrapping_add(rhs))
     }
 }
 
-impl std::fmt::Display for Reg {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for Reg {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "V{:X}", self.0)
     }
 }
 
+/// CHIP-8 registers and timers.
 #[derive(Clone, Debug, Default)]
 pub struct CPU {
     /// Program counter
@@ -49,14 +57,14 @@ pub struct CPU {
     pub reg: [u8; 16],
 }
 
-impl std::ops::Index<Reg> for CPU {
+impl core::ops::Index<Reg> for CPU {
     type Output = u8;
     fn index(&self, idx: Reg) -> &Self::Output {
         &self.reg[idx.0 as usize]
     }
 }
 
-impl std::ops::IndexMut<Reg> for CPU {
+impl core::ops::IndexMut<Reg> for CPU {
     fn index_mut(&mut self, idx: Reg) -> &mut Self::Output {
         &mut self.reg[idx.0 as usize]
     }
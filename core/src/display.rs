@@ -0,0 +1,75 @@
+/// Width
+pub const W: u8 = 64;
+
+/// Height
+pub const H: u8 = 32;
+
+/// Leftmost bit for masking pixels
+pub const MSB: u64 = 0x8000_0000_0000_0000;
+
+/// 64x32 monochrome pixel buffer.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Display {
+    /// Pixel grid
+    grid: [u64; H as usize],
+
+    /// Whether sprites are clipped at the edges instead of wrapping
+    clip: bool,
+}
+
+impl Display {
+    /// Width in pixels.
+    pub const WIDTH: u8 = W;
+
+    /// Height in pixels.
+    pub const HEIGHT: u8 = H;
+
+    /// Pixel rows from top to bottom, with the leftmost pixel in the most significant bit.
+    pub fn rows(&self) -> &[u64; H as usize] {
+        &self.grid
+    }
+
+    /// Pixels row by row from the top left, one byte each: 1 if set, 0 if not.
+    pub fn bytes(&self) -> [u8; W as usize * H as usize] {
+        let mut bytes = [0; W as usize * H as usize];
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = self.get((index % W as usize) as u8, (index / W as usize) as u8) as u8;
+        }
+        bytes
+    }
+
+    /// Blank out the display.
+    pub fn clear(&mut self) {
+        self.grid = [0; H as usize];
+    }
+
+    /// XOR sprite `rows` onto the display starting at `(x, y)`, one byte per row
+    /// with the most significant bit leftmost, and return whether any set pixel was unset.
+    ///
+    /// The starting coordinates always wrap around the display. Parts of the sprite
+    /// past the right or bottom edge wrap around too, or are dropped when clipping.
+    pub fn draw_sprite(&mut self, x: u8, y: u8, rows: &[u8]) -> bool {
+        let (x, y) = (x % W, y % H);
+        let mut collided = false;
+        for (dy, &byte) in rows.iter().enumerate() {
+            let row = y as usize + dy;
+            if self.clip && row >= H as usize { break }
+            let row = row % H as usize;
+            let sprite = (byte as u64) << 56;
+            let sprite = if self.clip { sprite >> x } else { sprite.rotate_right(x as u32) };
+            collided |= self.grid[row] & sprite > 0;
+            self.grid[row] ^= sprite;
+        }
+        collided
+    }
+
+    /// Clip sprites at the right and bottom edges instead of wrapping them around.
+    pub fn set_clip(&mut self, clip: bool) {
+        self.clip = clip;
+    }
+
+    /// Whether the pixel at coordinates `(x, y)` is set.
+    pub fn get(&self, x: u8, y: u8) -> bool {
+        x < W && y < H && self.grid[y as usize] & (MSB >> x) > 0
+    }
+}
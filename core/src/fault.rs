@@ -0,0 +1,68 @@
+use crate::ram;
+
+/// Invalid instruction, or invalid stack or memory access by the instruction at `pc`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// `CALL` with all 16 stack entries in use
+    StackOverflow { pc: ram::Addr },
+
+    /// `RET` with an empty stack
+    StackUnderflow { pc: ram::Addr },
+
+    /// Access to I + `offset` beyond address 0xFFF
+    Memory { pc: ram::Addr, idx: ram::Addr, offset: u16 },
+
+    /// Instruction word `op` that does not decode
+    Opcode { pc: ram::Addr, op: u16 },
+}
+
+impl core::fmt::Display for Fault {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+        | Fault::StackOverflow { pc } => write!(fmt, "Stack overflow at {}", pc),
+        | Fault::StackUnderflow { pc } => write!(fmt, "Stack underflow at {}", pc),
+        | Fault::Memory { pc, idx, offset } => write!(fmt, "Memory access at I ({}) + {:#X} beyond 0xFFF at {}", idx, offset, pc),
+        | Fault::Opcode { pc, op } => write!(fmt, "Invalid instruction {:#06X} at {}", op, pc),
+        }
+    }
+}
+
+impl core::error::Error for Fault {}
+
+/// How to handle faults.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Policy {
+    /// Stop before the faulting instruction, e.g. to inspect it in the debugger
+    #[default]
+    Trap,
+
+    /// Wrap stack and memory addresses around like hardware, but trap invalid instructions
+    Wrap,
+
+    /// Stop before the faulting instruction and exit with an error
+    Error,
+}
+
+/// Fault policy name that is not `trap`, `wrap`, or `error`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnknownPolicy;
+
+impl core::fmt::Display for UnknownPolicy {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "Unknown fault policy: expected `trap`, `wrap`, or `error`")
+    }
+}
+
+impl core::error::Error for UnknownPolicy {}
+
+impl core::str::FromStr for Policy {
+    type Err = UnknownPolicy;
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy {
+        | "trap" => Ok(Policy::Trap),
+        | "wrap" => Ok(Policy::Wrap),
+        | "error" => Ok(Policy::Error),
+        | _ => Err(UnknownPolicy),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::machine;
+
+/// `Host` for embedders that set keys directly, with a seeded xorshift RNG for `RND`.
+#[derive(Copy, Clone, Debug)]
+pub struct Input {
+    /// Key pressed since the last `LD Vx, K`
+    key: Option<u8>,
+
+    /// Key tapped since it was last checked, if not held down
+    tapped: Option<u8>,
+
+    /// Keys held down, one bit per key
+    held: u16,
+
+    /// Xorshift state, never zero
+    state: u32,
+}
+
+impl Input {
+    /// No keys held, with `RND` seeded by `seed`.
+    pub fn new(seed: u32) -> Self {
+        Input { key: None, tapped: None, held: 0, state: seed.max(1) }
+    }
+
+    /// Hold down CHIP-8 `key` until released.
+    pub fn press(&mut self, key: u8) {
+        let key = key & 0xF;
+        self.held |= 1 << key;
+        self.key = Some(key);
+        self.tapped = None;
+    }
+
+    /// Press CHIP-8 `key` without holding it down, for keyboards that report
+    /// presses but not releases: the next `SKP`, `SKNP`, or `LD Vx, K` sees it once.
+    pub fn tap(&mut self, key: u8) {
+        let key = key & 0xF;
+        self.key = Some(key);
+        self.tapped = Some(key);
+    }
+
+    /// Release CHIP-8 `key`.
+    pub fn release(&mut self, key: u8) {
+        self.held &= !(1 << (key & 0xF));
+    }
+
+    /// Whether CHIP-8 `key` is held down.
+    pub fn is_held(&self, key: u8) -> bool {
+        key < 16 && self.held & (1 << key) > 0
+    }
+
+    /// Forget the last key pressed, e.g. when loading another program.
+    pub fn forget(&mut self) {
+        self.key = None;
+        self.tapped = None;
+    }
+}
+
+impl machine::Host for Input {
+    fn random(&mut self) -> u8 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state >> 24) as u8
+    }
+
+    fn pressed(&mut self, key: u8) -> bool {
+        if self.tapped == Some(key) {
+            self.tapped = None;
+            self.key = None;
+            return true;
+        }
+        self.is_held(key)
+    }
+
+    fn key(&mut self) -> Option<u8> {
+        self.tapped = None;
+        self.key.take()
+    }
+}
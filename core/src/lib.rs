@@ -0,0 +1,46 @@
+//! CHIP-8 interpreter core, without `std` or `alloc`.
+//!
+//! A `Machine` holds the CPU, memory, stack, and pixel buffer, and executes
+//! instructions with randomness and keypad input from a `Host`:
+//!
+//! ```
+//! use chip_core::{Host, Machine, Options};
+//!
+//! struct Idle;
+//!
+//! impl Host for Idle {
+//!     fn random(&mut self) -> u8 { 4 }
+//!     fn pressed(&mut self, _: u8) -> bool { false }
+//!     fn key(&mut self) -> Option<u8> { None }
+//! }
+//!
+//! // Draw the font sprite for 0 at (0, 0)
+//! let mut machine = Machine::new([0xA0, 0x00, 0xD0, 0x05].iter().cloned(), Options::default());
+//! machine.step(&mut Idle, &mut ()).unwrap();
+//! machine.step(&mut Idle, &mut ()).unwrap();
+//! assert_eq!(machine.display.rows()[0] >> 60, 0xF);
+//! ```
+
+#![no_std]
+#![allow(clippy::upper_case_acronyms)]
+
+pub mod asm;
+pub mod cpu;
+pub mod display;
+pub mod fault;
+pub mod input;
+pub mod machine;
+pub mod quirks;
+pub mod ram;
+pub mod stack;
+pub mod trace;
+
+pub use asm::{Asm, AsmError};
+pub use cpu::{CPU, Reg};
+pub use display::Display;
+pub use fault::{Fault, Policy, UnknownPolicy};
+pub use input::Input;
+pub use machine::{Host, Machine, Options};
+pub use quirks::{Quirks, UnknownQuirks};
+pub use ram::{Addr, Mem, Overlap, FONT_SIZE};
+pub use trace::Trace;
@@ -0,0 +1,304 @@
+use crate::asm;
+use crate::cpu;
+use crate::display;
+use crate::fault;
+use crate::quirks;
+use crate::ram;
+use crate::stack;
+use crate::trace;
+
+/// Interpreter configuration.
+#[derive(Copy, Clone, Debug)]
+pub struct Options {
+    /// How to handle stack and memory faults
+    pub fault: fault::Policy,
+
+    /// Memory address where font data is stored
+    pub font: ram::Addr,
+
+    /// Memory address where programs are loaded and execution starts
+    pub load: ram::Addr,
+
+    /// Behaviors that differ between interpreters
+    pub quirks: quirks::Quirks,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            fault: fault::Policy::default(),
+            font: ram::FONT_OFFSET,
+            load: ram::PROGRAM_OFFSET,
+            quirks: quirks::Quirks::default(),
+        }
+    }
+}
+
+/// Randomness and keypad input, provided by the host running a `Machine`.
+pub trait Host {
+    /// Random byte for `RND`.
+    fn random(&mut self) -> u8;
+
+    /// Whether CHIP-8 `key` is pressed, for `SKP` and `SKNP`.
+    fn pressed(&mut self, key: u8) -> bool;
+
+    /// Key pressed since the last call, for `LD Vx, K`, or `None` to keep waiting.
+    fn key(&mut self) -> Option<u8>;
+}
+
+/// CHIP-8 interpreter state and instruction semantics, without any I/O.
+///
+/// Execute one instruction at a time with `Machine::step`, and decrement
+/// the timers at 60Hz with `Machine::tick`.
+#[derive(Clone)]
+pub struct Machine {
+    /// CHIP-8 CPU
+    pub cpu: cpu::CPU,
+
+    /// Random access memory
+    pub ram: ram::Mem,
+
+    /// Stack memory
+    pub stack: stack::Mem,
+
+    /// Pixel buffer
+    pub display: display::Display,
+
+    /// Interpreter configuration
+    pub options: Options,
+}
+
+impl Machine {
+    /// Create a new interpreter running `program` configured by `options`.
+    ///
+    /// Panics if the program overlaps the font data; see `Machine::try_new`.
+    pub fn new<B>(program: B, options: Options) -> Self where B: IntoIterator<Item = u8> {
+        Self::try_new(program, options).unwrap_or_else(|overlap| panic!("{}", overlap))
+    }
+
+    /// Create a new interpreter running `program` configured by `options`,
+    /// unless the program overlaps the font data.
+    pub fn try_new<B>(program: B, options: Options) -> Result<Self, ram::Overlap> where B: IntoIterator<Item = u8> {
+        let mut display = display::Display::default();
+        display.set_clip(options.quirks.clip);
+        Ok(Machine {
+            cpu: cpu::CPU { pc: options.load, ..cpu::CPU::default() },
+            ram: ram::Mem::try_new(program, options.load, options.font)?,
+            stack: stack::Mem::default(),
+            display,
+            options,
+        })
+    }
+
+    /// Decrement the sound and delay timers.
+    pub fn tick(&mut self) {
+        self.cpu.st = self.cpu.st.saturating_sub(1);
+        self.cpu.dt = self.cpu.dt.saturating_sub(1);
+    }
+
+    /// Execute a single instruction, with input from `host`, reporting memory
+    /// accesses to `trace`.
+    ///
+    /// Unless the fault policy is `Policy::Wrap`, a faulting instruction
+    /// has no effect and the program counter is left pointing at it.
+    /// Invalid instructions always fault this way.
+    pub fn step<H: Host, T: trace::Trace>(&mut self, host: &mut H, trace: &mut T) -> Result<(), fault::Fault> {
+
+        let pc = self.cpu.pc;
+        let hi = self.ram[pc];
+        let lo = self.ram[pc + 1];
+        let op = match asm::Asm::parse(hi, lo) {
+        | Some(op) => op,
+        | None => return Err(fault::Fault::Opcode { pc, op: u16::from_be_bytes([hi, lo]) }),
+        };
+
+        trace.exec(pc, op);
+
+        self.cpu.pc += 2;
+
+        let result = self.exec(pc, op, host, trace);
+        if result.is_err() { self.cpu.pc = pc; }
+        result
+    }
+
+    /// Check that address I + `offset` is in range, subject to the fault policy.
+    fn index(&self, pc: ram::Addr, offset: u16) -> Result<(), fault::Fault> {
+        match self.cpu.idx.checked_add(offset) {
+        | Some(_) => Ok(()),
+        | None if self.options.fault == fault::Policy::Wrap => Ok(()),
+        | None => Err(fault::Fault::Memory { pc, idx: self.cpu.idx, offset }),
+        }
+    }
+
+    /// Execute `op`, fetched from `pc`.
+    fn exec<H: Host, T: trace::Trace>(&mut self, pc: ram::Addr, op: asm::Asm, host: &mut H, trace: &mut T) -> Result<(), fault::Fault> {
+
+        use asm::Asm::*;
+
+        match op {
+        | SYS(_) => (),
+        | CLS => {
+            self.display.clear();
+        }
+        | RET => {
+            let slot = match self.cpu.sp.pop() {
+            | Some(slot) => slot,
+            | None if self.options.fault == fault::Policy::Wrap => self.cpu.sp.wrapping_pop(),
+            | None => return Err(fault::Fault::StackUnderflow { pc }),
+            };
+            self.cpu.pc = self.stack[slot];
+        }
+        | JP(addr) => {
+            self.cpu.pc = addr;
+        }
+        | CALL(addr) => {
+            let slot = match self.cpu.sp.push() {
+            | Some(slot) => slot,
+            | None if self.options.fault == fault::Policy::Wrap => self.cpu.sp.wrapping_push(),
+            | None => return Err(fault::Fault::StackOverflow { pc }),
+            };
+            self.stack[slot] = self.cpu.pc;
+            self.cpu.pc = addr;
+        }
+        | SEC(x, kk) => {
+            if self.cpu[x] == kk { self.cpu.pc += 2; }
+        }
+        | SNEC(x, kk) => {
+            if self.cpu[x] != kk { self.cpu.pc += 2; }
+        }
+        | SER(x, y) => {
+            if self.cpu[x] == self.cpu[y] { self.cpu.pc += 2; }
+        }
+        | SNER(x, y) => {
+            if self.cpu[x] != self.cpu[y] { self.cpu.pc += 2; }
+        }
+        | LDC(x, kk) => {
+            self.cpu[x] = kk;
+        }
+        | ADDC(x, kk) => {
+            self.cpu[x] = self.cpu[x].wrapping_add(kk);
+        }
+        | LDR(x, y) => {
+            self.cpu[x] = self.cpu[y];
+        }
+        | OR(x, y) => {
+            self.cpu[x] |= self.cpu[y];
+            if self.options.quirks.vf_reset { self.cpu[cpu::VF] = 0; }
+        }
+        | AND(x, y) => {
+            self.cpu[x] &= self.cpu[y];
+            if self.options.quirks.vf_reset { self.cpu[cpu::VF] = 0; }
+        }
+        | XOR(x, y) => {
+            self.cpu[x] ^= self.cpu[y];
+            if self.options.quirks.vf_reset { self.cpu[cpu::VF] = 0; }
+        }
+
+        // VF is written last, so that it holds the flag even when it is also Vx.
+        | ADDR(x, y) => {
+            let (z, carry) = self.cpu[x].overflowing_add(self.cpu[y]);
+            self.cpu[x] = z;
+            self.cpu[cpu::VF] = carry as u8;
+        }
+        | SUB(x, y) => {
+            let vx = self.cpu[x];
+            let vy = self.cpu[y];
+            self.cpu[x] = vx.wrapping_sub(vy);
+            self.cpu[cpu::VF] = (vx >= vy) as u8;
+        }
+        | SHR(x, y) => {
+            let v = if self.options.quirks.shift { self.cpu[x] } else { self.cpu[y] };
+            self.cpu[x] = v >> 0x01;
+            self.cpu[cpu::VF] = v & 0x01;
+        }
+        | SUBN(x, y) => {
+            let vx = self.cpu[x];
+            let vy = self.cpu[y];
+            self.cpu[x] = vy.wrapping_sub(vx);
+            self.cpu[cpu::VF] = (vy >= vx) as u8;
+        }
+        | SHL(x, y) => {
+            let v = if self.options.quirks.shift { self.cpu[x] } else { self.cpu[y] };
+            self.cpu[x] = v << 0x01;
+            self.cpu[cpu::VF] = v >> 0x07;
+        }
+        | LDI(addr) => {
+            self.cpu.idx = addr;
+        }
+        | JO(addr) => {
+            let offset = if self.options.quirks.jump { cpu::Reg::from((u16::from(addr) >> 8) as u8) } else { cpu::V0 };
+            self.cpu.pc = addr + self.cpu[offset] as u16;
+        }
+        | RND(x, kk) => {
+            self.cpu[x] = host.random() & kk;
+        }
+        | DRW(x, y, n) => {
+            if n > 0 { self.index(pc, n as u16 - 1)?; }
+            let mut rows = [0; 16];
+            for dy in 0..n {
+                let addr = self.cpu.idx + dy as u16;
+                rows[dy as usize] = self.ram[addr];
+                trace.read(addr);
+            }
+            let collided = self.display.draw_sprite(self.cpu[x], self.cpu[y], &rows[..n as usize]);
+            self.cpu[cpu::VF] = collided as u8;
+        }
+        | SKP(x) => {
+            if host.pressed(self.cpu[x]) { self.cpu.pc += 2; }
+        }
+        | SKNP(x) => {
+            if !host.pressed(self.cpu[x]) { self.cpu.pc += 2; }
+        }
+        | LDTR(x) => {
+            self.cpu[x] = self.cpu.dt;
+        }
+        | LDK(x) => {
+            match host.key() {
+            | None => { self.cpu.pc -= 2; }
+            | Some(k) => { self.cpu[x] = k },
+            }
+        }
+        | LDRT(x) => {
+            self.cpu.dt = self.cpu[x];
+        }
+        | LDRS(x) => {
+            self.cpu.st = self.cpu[x];
+        }
+        | ADDI(x) => {
+            self.cpu.idx += self.cpu[x] as u16;
+        }
+        | LDS(x) => {
+            self.cpu.idx = self.options.font + (self.cpu[x] as u16 * 5);
+        }
+        | LDB(x) => {
+            self.index(pc, 2)?;
+            let vx = self.cpu[x];
+            for (offset, digit) in [(vx / 100) % 10, (vx / 10) % 10, vx % 10].iter().enumerate() {
+                let addr = self.cpu.idx + offset as u16;
+                self.ram[addr] = *digit;
+                trace.write(addr, *digit);
+            }
+        }
+        | WR(x) => {
+            self.index(pc, x as u16)?;
+            for offset in 0..=x {
+                let addr = self.cpu.idx + offset as u16;
+                self.ram[addr] = self.cpu[cpu::V0 + offset];
+                trace.write(addr, self.ram[addr]);
+            }
+            if self.options.quirks.memory { self.cpu.idx += x as u16 + 1; }
+        }
+        | RD(x) => {
+            self.index(pc, x as u16)?;
+            for offset in 0..=x {
+                let addr = self.cpu.idx + offset as u16;
+                self.cpu[cpu::V0 + offset] = self.ram[addr];
+                trace.read(addr);
+            }
+            if self.options.quirks.memory { self.cpu.idx += x as u16 + 1; }
+        }
+        };
+
+        Ok(())
+    }
+}
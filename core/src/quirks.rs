@@ -0,0 +1,67 @@
+/// Behaviors that differ between CHIP-8 interpreters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Quirks {
+    /// Whether `OR`, `AND`, and `XOR` reset VF to 0
+    pub vf_reset: bool,
+
+    /// Whether `LD [I], Vx` and `LD Vx, [I]` leave I pointing past the last register
+    pub memory: bool,
+
+    /// Whether `SHR` and `SHL` shift Vx in place instead of shifting Vy into Vx
+    pub shift: bool,
+
+    /// Whether `JP V0, addr` jumps to addr plus Vx, where x is the high nibble of addr
+    pub jump: bool,
+
+    /// Whether sprites are clipped at the screen edges instead of wrapping around
+    pub clip: bool,
+}
+
+impl Quirks {
+    /// All preset names.
+    pub const PRESETS: [&'static str; 4] = ["default", "vip", "schip", "xochip"];
+
+    /// Look up a preset by name.
+    pub fn preset(name: &str) -> Result<Self, UnknownQuirks> {
+        let (vf_reset, memory, shift, jump, clip) = match name {
+        | "default" => return Ok(Quirks::default()),
+        | "vip" => (true, true, false, false, true),
+        | "schip" => (false, false, true, true, true),
+        | "xochip" => (false, true, false, false, false),
+        | _ => return Err(UnknownQuirks),
+        };
+        Ok(Quirks { vf_reset, memory, shift, jump, clip })
+    }
+}
+
+impl Default for Quirks {
+    /// Shifts in place, advances I on loads and stores, and wraps sprites around.
+    fn default() -> Self {
+        Quirks {
+            vf_reset: false,
+            memory: true,
+            shift: true,
+            jump: false,
+            clip: false,
+        }
+    }
+}
+
+/// Quirks preset name not in `Quirks::PRESETS`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnknownQuirks;
+
+impl core::fmt::Display for UnknownQuirks {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "Unknown quirks: expected `default`, `vip`, `schip`, or `xochip`")
+    }
+}
+
+impl core::error::Error for UnknownQuirks {}
+
+impl core::str::FromStr for Quirks {
+    type Err = UnknownQuirks;
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Quirks::preset(name)
+    }
+}
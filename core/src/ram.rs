@@ -0,0 +1,170 @@
+/// 12-bit memory address.
+///
+/// Arithmetic operators wrap around at 0xFFF like hardware; use
+/// `Addr::checked_add` or `Addr::offset` to detect overflow instead.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Addr(u16);
+
+impl Addr {
+    /// Add `rhs`, or return `None` if the sum is beyond 0xFFF.
+    pub fn checked_add(&self, rhs: u16) -> Option<Self> {
+        self.offset(rhs as i32)
+    }
+
+    /// Add signed `offset`, or return `None` if the sum is outside 0x000 through 0xFFF.
+    pub fn offset(&self, offset: i32) -> Option<Self> {
+        let sum = self.0 as i32 + offset;
+        if !(0..=0x0FFF).contains(&sum) { None } else { Some(Addr(sum as u16)) }
+    }
+}
+
+impl Default for Addr {
+    fn default() -> Self {
+        Addr(0x0200)
+    }
+}
+
+impl From<u16> for Addr {
+    fn from(addr: u16) -> Self {
+        Addr(addr & 0x0FFF)
+    }
+}
+
+impl From<Addr> for u16 {
+    fn from(addr: Addr) -> Self {
+        addr.0
+    }
+}
+
+impl core::ops::Add<u16> for Addr {
+    type Output = Self; 
+    fn add(self, rhs: u16) -> Self::Output {
+        Addr::from(self.0.wrapping_add(rhs))
+    }
+}
+
+impl core::ops::AddAssign<u16> for Addr {
+    fn add_assign(&mut self, rhs: u16) {
+        *self = *self + rhs
+    }
+}
+
+impl core::ops::Sub<u16> for Addr {
+    type Output = Self; 
+    fn sub(self, rhs: u16) -> Self::Output {
+        Addr::from(self.0.wrapping_sub(rhs))
+    }
+}
+
+impl core::ops::SubAssign<u16> for Addr {
+    fn sub_assign(&mut self, rhs: u16) {
+        *self = *self - rhs
+    }
+}
+
+impl core::fmt::Display for Addr {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:#05X}", self.0)
+    }
+}
+
+/// Default memory address where font data is stored.
+pub const FONT_OFFSET: Addr = Addr(0x00_0000);
+
+/// Size of font data in bytes.
+pub const FONT_SIZE: u16 = 80;
+
+/// Memory address where programs are loaded.
+/// Addresses below are reserved for the interpreter.
+pub const PROGRAM_OFFSET: Addr = Addr(0x00_0200);
+
+/// CHIP-8 built-in binary font data.
+/// Taken from [@wernsey][0].
+///
+/// [0]: https://github.com/wernsey/chip8/blob/a2310220ce5c205c74d85bc69125339641ee838a/chip8.c#L56-L74
+const FONT: [u8; FONT_SIZE as usize] = [
+    /* 0 */ 0xF0, 0x90, 0x90, 0x90, 0xF0,
+    /* 1 */ 0x20, 0x60, 0x20, 0x20, 0x70,
+    /* 2 */ 0xF0, 0x10, 0xF0, 0x80, 0xF0,
+    /* 3 */ 0xF0, 0x10, 0xF0, 0x10, 0xF0,
+    /* 4 */ 0x90, 0x90, 0xF0, 0x10, 0x10,
+    /* 5 */ 0xF0, 0x80, 0xF0, 0x10, 0xF0,
+    /* 6 */ 0xF0, 0x80, 0xF0, 0x90, 0xF0,
+    /* 7 */ 0xF0, 0x10, 0x20, 0x40, 0x40,
+    /* 8 */ 0xF0, 0x90, 0xF0, 0x90, 0xF0,
+    /* 9 */ 0xF0, 0x90, 0xF0, 0x10, 0xF0,
+    /* A */ 0xF0, 0x90, 0xF0, 0x90, 0x90,
+    /* B */ 0xE0, 0x90, 0xE0, 0x90, 0xE0,
+    /* C */ 0xF0, 0x80, 0x80, 0x80, 0xF0,
+    /* D */ 0xE0, 0x80, 0x80, 0x80, 0xE0,
+    /* E */ 0xF0, 0x80, 0xF0, 0x80, 0xF0,
+    /* F */ 0xF0, 0x80, 0xF0, 0x80, 0x80,
+];
+
+/// Font data and program loaded over each other.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Overlap {
+    /// Memory address of the font data
+    pub font: Addr,
+
+    /// Memory address of the program
+    pub load: Addr,
+}
+
+impl core::fmt::Display for Overlap {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "Font data at {} overlaps the program loaded at {}", self.font, self.load)
+    }
+}
+
+impl core::error::Error for Overlap {}
+
+/// 4096-byte random access memory.
+#[derive(Clone)]
+pub struct Mem([u8; 4096]);
+
+impl Mem {
+    /// Load font data at `font` and `program` at `load`.
+    ///
+    /// Panics if they overlap; see `Mem::try_new`.
+    pub fn new<B>(program: B, load: Addr, font: Addr) -> Self where B: IntoIterator<Item = u8> {
+        Self::try_new(program, load, font).unwrap_or_else(|overlap| panic!("{}", overlap))
+    }
+
+    /// Load font data at `font` and `program` at `load`, unless they overlap.
+    pub fn try_new<B>(program: B, load: Addr, font: Addr) -> Result<Self, Overlap> where B: IntoIterator<Item = u8> {
+        let mut mem = [0; 4096];
+        let len = mem.iter_mut()
+            .skip(load.0 as usize)
+            .zip(program)
+            .map(|(lhs, rhs)| *lhs = rhs)
+            .count();
+        let (start, end) = (font.0 as usize, font.0 as usize + FONT_SIZE as usize);
+        if len > 0 && start < load.0 as usize + len && (load.0 as usize) < end {
+            return Err(Overlap { font, load });
+        }
+        mem.iter_mut()
+            .skip(start)
+            .zip(FONT.iter())
+            .for_each(|(lhs, rhs)| *lhs = *rhs);
+        Ok(Mem(mem))
+    }
+
+    /// All 4096 bytes of memory.
+    pub fn as_bytes(&self) -> &[u8; 4096] {
+        &self.0
+    }
+}
+
+impl core::ops::Index<Addr> for Mem {
+    type Output = u8;
+    fn index(&self, addr: Addr) -> &Self::Output {
+        &self.0[addr.0 as usize]
+    }
+}
+
+impl core::ops::IndexMut<Addr> for Mem {
+    fn index_mut(&mut self, addr: Addr) -> &mut Self::Output {
+        &mut self.0[addr.0 as usize]
+    }
+}
@@ -0,0 +1,74 @@
+use crate::ram;
+
+/// 8-bit stack address
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Addr(u8);
+
+/// Number of return addresses the stack can hold.
+const DEPTH: u8 = 16;
+
+impl Addr {
+    /// Push onto the stack, returning the slot to write, or `None` if full.
+    pub fn push(&mut self) -> Option<Addr> {
+        if self.0 >= DEPTH { return None }
+        let slot = *self;
+        self.0 += 1;
+        Some(slot)
+    }
+
+    /// Pop off the stack, returning the slot to read, or `None` if empty.
+    pub fn pop(&mut self) -> Option<Addr> {
+        if self.0 == 0 { return None }
+        self.0 -= 1;
+        Some(*self)
+    }
+
+    /// Push onto the stack, wrapping around to the bottom when full.
+    pub fn wrapping_push(&mut self) -> Addr {
+        let slot = Addr(self.0 % DEPTH);
+        self.0 = slot.0 + 1;
+        slot
+    }
+
+    /// Pop off the stack, wrapping around to the top when empty.
+    pub fn wrapping_pop(&mut self) -> Addr {
+        self.0 = (self.0 + DEPTH - 1) % DEPTH;
+        *self
+    }
+}
+
+impl From<Addr> for u8 {
+    fn from(addr: Addr) -> Self {
+        addr.0
+    }
+}
+
+impl core::fmt::Display for Addr {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:#X}", self.0)
+    }
+}
+
+/// 64-byte stack memory
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Mem([ram::Addr; DEPTH as usize]);
+
+impl Mem {
+    /// Return addresses currently on the stack, from bottom to top.
+    pub fn frames(&self, sp: Addr) -> &[ram::Addr] {
+        &self.0[..sp.0 as usize]
+    }
+}
+
+impl core::ops::Index<Addr> for Mem {
+    type Output = ram::Addr;
+    fn index(&self, addr: Addr) -> &Self::Output {
+        &self.0[addr.0 as usize]
+    }
+}
+
+impl core::ops::IndexMut<Addr> for Mem {
+    fn index_mut(&mut self, addr: Addr) -> &mut Self::Output {
+        &mut self.0[addr.0 as usize]
+    }
+}
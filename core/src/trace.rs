@@ -0,0 +1,64 @@
+use crate::asm;
+use crate::ram;
+
+/// Observer of instructions and memory accesses made by `Machine::step`.
+///
+/// All methods default to doing nothing, so implementors only
+/// override the events they care about.
+pub trait Trace {
+    /// Called before executing `op`, fetched from `addr`.
+    fn exec(&mut self, _addr: ram::Addr, _op: asm::Asm) {}
+
+    /// Called after an instruction reads data from `addr`.
+    fn read(&mut self, _addr: ram::Addr) {}
+
+    /// Called after an instruction writes `byte` to `addr`.
+    fn write(&mut self, _addr: ram::Addr, _byte: u8) {}
+}
+
+impl Trace for () {}
+
+impl<T: Trace + ?Sized> Trace for &mut T {
+    fn exec(&mut self, addr: ram::Addr, op: asm::Asm) {
+        (**self).exec(addr, op)
+    }
+
+    fn read(&mut self, addr: ram::Addr) {
+        (**self).read(addr)
+    }
+
+    fn write(&mut self, addr: ram::Addr, byte: u8) {
+        (**self).write(addr, byte)
+    }
+}
+
+impl<T: Trace> Trace for Option<T> {
+    fn exec(&mut self, addr: ram::Addr, op: asm::Asm) {
+        if let Some(trace) = self { trace.exec(addr, op) }
+    }
+
+    fn read(&mut self, addr: ram::Addr) {
+        if let Some(trace) = self { trace.read(addr) }
+    }
+
+    fn write(&mut self, addr: ram::Addr, byte: u8) {
+        if let Some(trace) = self { trace.write(addr, byte) }
+    }
+}
+
+impl<A: Trace, B: Trace> Trace for (A, B) {
+    fn exec(&mut self, addr: ram::Addr, op: asm::Asm) {
+        self.0.exec(addr, op);
+        self.1.exec(addr, op);
+    }
+
+    fn read(&mut self, addr: ram::Addr) {
+        self.0.read(addr);
+        self.1.read(addr);
+    }
+
+    fn write(&mut self, addr: ram::Addr, byte: u8) {
+        self.0.write(addr, byte);
+        self.1.write(addr, byte);
+    }
+}
@@ -0,0 +1,16 @@
+//! Generate `chip.h` from the `extern "C"` functions in `src/lib.rs` into `OUT_DIR`.
+//! The `header` test checks it against the committed `include/chip.h`.
+
+fn main() {
+    let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out = std::env::var("OUT_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", dir)).unwrap();
+    cbindgen::Builder::new()
+        .with_crate(&dir)
+        .with_config(config)
+        .generate()
+        .expect("Unable to generate C header")
+        .write_to_file(format!("{}/chip.h", out));
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
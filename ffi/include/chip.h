@@ -0,0 +1,98 @@
+#ifndef CHIP_H
+#define CHIP_H
+
+/* Generated by cbindgen from ffi/src/lib.rs. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Display width in pixels.
+#define CHIP_WIDTH 64
+
+// Display height in pixels.
+#define CHIP_HEIGHT 32
+
+// Most ROM bytes that fit in memory after 0x200.
+#define CHIP_ROM_SIZE (4096 - 512)
+
+// Outcome of a call.
+typedef enum ChipStatus {
+  // Success
+  CHIP_STATUS_OK,
+  // Null pointer argument
+  CHIP_STATUS_NULL,
+  // ROM longer than `CHIP_ROM_SIZE`
+  CHIP_STATUS_TOO_LARGE,
+  // `CALL` with all 16 stack entries in use
+  CHIP_STATUS_STACK_OVERFLOW,
+  // `RET` with an empty stack
+  CHIP_STATUS_STACK_UNDERFLOW,
+  // Access to memory beyond address 0xFFF
+  CHIP_STATUS_MEMORY,
+  // Instruction word that does not decode
+  CHIP_STATUS_OPCODE,
+} ChipStatus;
+
+// CHIP-8 emulator, opaque to C.
+typedef struct Chip Chip;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create an emulator with no program loaded, seeding `RND` with `seed`.
+// Free it with `chip_free`.
+struct Chip *chip_new(uint32_t seed);
+
+// Reset the emulator and load `len` bytes of `rom` at 0x200.
+//
+// # Safety
+//
+// `chip` must come from `chip_new`, and `rom` must point to `len` readable bytes.
+enum ChipStatus chip_load(struct Chip *chip, const uint8_t *rom, uintptr_t len);
+
+// Execute a single instruction, without advancing the timers. A faulting
+// instruction has no effect, and faults again if stepped again.
+//
+// # Safety
+//
+// `chip` must come from `chip_new`.
+enum ChipStatus chip_step(struct Chip *chip);
+
+// Execute one 60Hz frame: 10 instructions, then decrement the timers.
+// Stops without decrementing them at the first fault.
+//
+// # Safety
+//
+// `chip` must come from `chip_new`.
+enum ChipStatus chip_run_frame(struct Chip *chip);
+
+// Press or release CHIP-8 `key`, from 0x0 to 0xF.
+//
+// # Safety
+//
+// `chip` must come from `chip_new`.
+enum ChipStatus chip_set_key(struct Chip *chip, uint8_t key, bool pressed);
+
+// Pixels row by row from the top left, `CHIP_WIDTH * CHIP_HEIGHT` bytes:
+// 1 if set, 0 if not. Returns null if `chip` is null.
+//
+// # Safety
+//
+// `chip` must come from `chip_new`. The pixels are valid until the next call with `chip`.
+const uint8_t *chip_framebuffer(struct Chip *chip);
+
+// Free an emulator created by `chip_new`. Does nothing if `chip` is null.
+//
+// # Safety
+//
+// `chip` must come from `chip_new`, and not be used afterwards.
+void chip_free(struct Chip *chip);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CHIP_H */
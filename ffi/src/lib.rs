@@ -0,0 +1,196 @@
+//! C ABI for the interpreter core. `build.rs` generates the header `include/chip.h`.
+//!
+//! ```c
+//! #include "chip.h"
+//!
+//! Chip *chip = chip_new(42);
+//! chip_load(chip, rom, rom_len);
+//! while (chip_run_frame(chip) == CHIP_STATUS_OK) {
+//!     const uint8_t *pixels = chip_framebuffer(chip);
+//!     chip_set_key(chip, 0x5, is_down('w'));
+//! }
+//! chip_free(chip);
+//! ```
+
+use chip_core::fault;
+use chip_core::input;
+use chip_core::machine;
+use chip_core::ram;
+
+/// Display width in pixels.
+pub const CHIP_WIDTH: usize = 64;
+
+/// Display height in pixels.
+pub const CHIP_HEIGHT: usize = 32;
+
+/// Most ROM bytes that fit in memory after 0x200.
+pub const CHIP_ROM_SIZE: usize = 0x1000 - 0x200;
+
+const _: () = assert!(CHIP_WIDTH == chip_core::Display::WIDTH as usize && CHIP_HEIGHT == chip_core::Display::HEIGHT as usize);
+
+/// Instructions per 60Hz frame.
+const CYCLES: u32 = 10;
+
+/// Outcome of a call.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChipStatus {
+    /// Success
+    Ok,
+
+    /// Null pointer argument
+    Null,
+
+    /// ROM longer than `CHIP_ROM_SIZE`
+    TooLarge,
+
+    /// `CALL` with all 16 stack entries in use
+    StackOverflow,
+
+    /// `RET` with an empty stack
+    StackUnderflow,
+
+    /// Access to memory beyond address 0xFFF
+    Memory,
+
+    /// Instruction word that does not decode
+    Opcode,
+}
+
+impl From<Result<(), fault::Fault>> for ChipStatus {
+    fn from(result: Result<(), fault::Fault>) -> Self {
+        match result {
+        | Ok(()) => ChipStatus::Ok,
+        | Err(fault::Fault::StackOverflow { .. }) => ChipStatus::StackOverflow,
+        | Err(fault::Fault::StackUnderflow { .. }) => ChipStatus::StackUnderflow,
+        | Err(fault::Fault::Memory { .. }) => ChipStatus::Memory,
+        | Err(fault::Fault::Opcode { .. }) => ChipStatus::Opcode,
+        }
+    }
+}
+
+/// CHIP-8 emulator, opaque to C.
+pub struct Chip {
+    /// Interpreter state
+    machine: machine::Machine,
+
+    /// Keypad and random number input
+    input: input::Input,
+
+    /// Pixels as last returned by `chip_framebuffer`
+    framebuffer: [u8; CHIP_WIDTH * CHIP_HEIGHT],
+}
+
+/// Create an emulator with no program loaded, seeding `RND` with `seed`.
+/// Free it with `chip_free`.
+#[no_mangle]
+pub extern "C" fn chip_new(seed: u32) -> *mut Chip {
+    let chip = Chip {
+        machine: machine::Machine::new(core::iter::empty(), machine::Options::default()),
+        input: input::Input::new(seed),
+        framebuffer: [0; CHIP_WIDTH * CHIP_HEIGHT],
+    };
+    Box::into_raw(Box::new(chip))
+}
+
+/// Reset the emulator and load `len` bytes of `rom` at 0x200.
+///
+/// # Safety
+///
+/// `chip` must come from `chip_new`, and `rom` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chip_load(chip: *mut Chip, rom: *const u8, len: usize) -> ChipStatus {
+    let chip = match chip.as_mut() {
+    | Some(chip) => chip,
+    | None => return ChipStatus::Null,
+    };
+    let rom = match (rom.is_null(), len) {
+    | (_, 0) => &[][..],
+    | (true, _) => return ChipStatus::Null,
+    | (false, len) if len > CHIP_ROM_SIZE => return ChipStatus::TooLarge,
+    | (false, len) => core::slice::from_raw_parts(rom, len),
+    };
+    let options = machine::Options { load: ram::PROGRAM_OFFSET, ..chip.machine.options };
+    chip.machine = machine::Machine::new(rom.iter().cloned(), options);
+    chip.input.forget();
+    ChipStatus::Ok
+}
+
+/// Execute a single instruction, without advancing the timers. A faulting
+/// instruction has no effect, and faults again if stepped again.
+///
+/// # Safety
+///
+/// `chip` must come from `chip_new`.
+#[no_mangle]
+pub unsafe extern "C" fn chip_step(chip: *mut Chip) -> ChipStatus {
+    match chip.as_mut() {
+    | Some(chip) => chip.machine.step(&mut chip.input, &mut ()).into(),
+    | None => ChipStatus::Null,
+    }
+}
+
+/// Execute one 60Hz frame: 10 instructions, then decrement the timers.
+/// Stops without decrementing them at the first fault.
+///
+/// # Safety
+///
+/// `chip` must come from `chip_new`.
+#[no_mangle]
+pub unsafe extern "C" fn chip_run_frame(chip: *mut Chip) -> ChipStatus {
+    let chip = match chip.as_mut() {
+    | Some(chip) => chip,
+    | None => return ChipStatus::Null,
+    };
+    for _ in 0..CYCLES {
+        if let Err(fault) = chip.machine.step(&mut chip.input, &mut ()) {
+            return Err(fault).into();
+        }
+    }
+    chip.machine.tick();
+    ChipStatus::Ok
+}
+
+/// Press or release CHIP-8 `key`, from 0x0 to 0xF.
+///
+/// # Safety
+///
+/// `chip` must come from `chip_new`.
+#[no_mangle]
+pub unsafe extern "C" fn chip_set_key(chip: *mut Chip, key: u8, pressed: bool) -> ChipStatus {
+    match chip.as_mut() {
+    | Some(chip) if pressed => chip.input.press(key),
+    | Some(chip) => chip.input.release(key),
+    | None => return ChipStatus::Null,
+    }
+    ChipStatus::Ok
+}
+
+/// Pixels row by row from the top left, `CHIP_WIDTH * CHIP_HEIGHT` bytes:
+/// 1 if set, 0 if not. Returns null if `chip` is null.
+///
+/// # Safety
+///
+/// `chip` must come from `chip_new`. The pixels are valid until the next call with `chip`.
+#[no_mangle]
+pub unsafe extern "C" fn chip_framebuffer(chip: *mut Chip) -> *const u8 {
+    match chip.as_mut() {
+    | Some(chip) => {
+        chip.framebuffer = chip.machine.display.bytes();
+        chip.framebuffer.as_ptr()
+    }
+    | None => core::ptr::null(),
+    }
+}
+
+/// Free an emulator created by `chip_new`. Does nothing if `chip` is null.
+///
+/// # Safety
+///
+/// `chip` must come from `chip_new`, and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn chip_free(chip: *mut Chip) {
+    if !chip.is_null() {
+        drop(Box::from_raw(chip));
+    }
+}
@@ -0,0 +1,43 @@
+//! Checks that the committed header matches the one generated by `build.rs`,
+//! then compiles `tests/c/chip_test.c` against it and the `cdylib`, and runs it.
+//!
+//! Set `CHIP_BLESS=1` to copy the generated header over `include/chip.h`.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn header() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/chip.h"));
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("include/chip.h");
+    if std::env::var_os("CHIP_BLESS").is_some() {
+        std::fs::write(&path, generated).unwrap();
+        return;
+    }
+    let committed = std::fs::read_to_string(&path).unwrap();
+    assert!(committed == generated, "{} is out of date (run with CHIP_BLESS=1 to update it)", path.display());
+}
+
+#[test]
+fn c_program() {
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let exe = std::env::current_exe().unwrap();
+    let lib = exe.parent().and_then(|deps| deps.parent()).unwrap();
+    let out = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("chip_test");
+    let cc = std::env::var("CC").unwrap_or_else(|_| String::from("cc"));
+
+    let status = Command::new(cc)
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror"])
+        .arg("-I").arg(manifest.join("include"))
+        .arg(manifest.join("tests/c/chip_test.c"))
+        .arg("-o").arg(&out)
+        .arg("-L").arg(lib)
+        .arg(format!("-Wl,-rpath,{}", lib.display()))
+        .arg("-lchip_ffi")
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "compiling chip_test.c failed");
+
+    let status = Command::new(&out).status().unwrap();
+    assert!(status.success(), "chip_test failed");
+}
@@ -0,0 +1,70 @@
+/* Drives the emulator through the C ABI, exiting nonzero on the first failed check. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "chip.h"
+
+#define CHECK(condition)                                                \
+    do {                                                                \
+        if (!(condition)) {                                             \
+            fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #condition); \
+            return 1;                                                   \
+        }                                                               \
+    } while (0)
+
+static int count(const uint8_t *pixels) {
+    int set = 0;
+    for (int i = 0; i < CHIP_WIDTH * CHIP_HEIGHT; i++) set += pixels[i];
+    return set;
+}
+
+int main(void) {
+    Chip *chip = chip_new(42);
+    CHECK(chip != NULL);
+    CHECK(count(chip_framebuffer(chip)) == 0);
+
+    /* Draw the font sprite for 0 at (0, 0), then loop */
+    const uint8_t draw[] = { 0xA0, 0x00, 0xD0, 0x05, 0x12, 0x04 };
+    CHECK(chip_load(chip, draw, sizeof draw) == CHIP_STATUS_OK);
+    CHECK(chip_step(chip) == CHIP_STATUS_OK);
+    CHECK(count(chip_framebuffer(chip)) == 0);
+    CHECK(chip_step(chip) == CHIP_STATUS_OK);
+    const uint8_t *pixels = chip_framebuffer(chip);
+    CHECK(count(pixels) == 14);
+    CHECK(memcmp(pixels, "\1\1\1\1\0", 5) == 0);
+    CHECK(memcmp(pixels + CHIP_WIDTH, "\1\0\0\1\0", 5) == 0);
+
+    /* Wait for a key, then draw its font sprite at (0, 0) */
+    const uint8_t wait[] = { 0xF0, 0x0A, 0xF0, 0x29, 0x61, 0x00, 0xD1, 0x15, 0x12, 0x08 };
+    CHECK(chip_load(chip, wait, sizeof wait) == CHIP_STATUS_OK);
+    CHECK(chip_run_frame(chip) == CHIP_STATUS_OK);
+    CHECK(count(chip_framebuffer(chip)) == 0);
+    CHECK(chip_set_key(chip, 0x7, true) == CHIP_STATUS_OK);
+    CHECK(chip_set_key(chip, 0x7, false) == CHIP_STATUS_OK);
+    CHECK(chip_run_frame(chip) == CHIP_STATUS_OK);
+    pixels = chip_framebuffer(chip);
+    CHECK(memcmp(pixels, "\1\1\1\1", 4) == 0);
+    CHECK(memcmp(pixels + CHIP_WIDTH, "\0\0\0\1", 4) == 0);
+
+    /* Faults leave the program counter at the faulting instruction */
+    const uint8_t ret[] = { 0x00, 0xEE };
+    CHECK(chip_load(chip, ret, sizeof ret) == CHIP_STATUS_OK);
+    CHECK(chip_run_frame(chip) == CHIP_STATUS_STACK_UNDERFLOW);
+    CHECK(chip_step(chip) == CHIP_STATUS_STACK_UNDERFLOW);
+    const uint8_t invalid[] = { 0x50, 0x01 };
+    CHECK(chip_load(chip, invalid, sizeof invalid) == CHIP_STATUS_OK);
+    CHECK(chip_step(chip) == CHIP_STATUS_OPCODE);
+
+    /* Invalid arguments */
+    static uint8_t large[CHIP_ROM_SIZE + 1];
+    CHECK(chip_load(chip, large, sizeof large) == CHIP_STATUS_TOO_LARGE);
+    CHECK(chip_load(chip, large, CHIP_ROM_SIZE) == CHIP_STATUS_OK);
+    CHECK(chip_load(chip, NULL, 2) == CHIP_STATUS_NULL);
+    CHECK(chip_step(NULL) == CHIP_STATUS_NULL);
+    CHECK(chip_framebuffer(NULL) == NULL);
+
+    chip_free(chip);
+    chip_free(NULL);
+    return 0;
+}
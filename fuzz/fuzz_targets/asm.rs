@@ -0,0 +1,18 @@
+#![no_main]
+
+use chip::Asm;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let [hi, lo] = *data {
+        if let Some(op) = Asm::parse(hi, lo) {
+            assert_eq!(op.encode(), u16::from_be_bytes([hi, lo]));
+            assert_eq!(op.to_string().parse::<Asm>(), Ok(op));
+        }
+    }
+    if let Ok(text) = std::str::from_utf8(data) {
+        if let Ok(op) = text.parse::<Asm>() {
+            assert_eq!(op.to_string().parse::<Asm>(), Ok(op));
+        }
+    }
+});
@@ -0,0 +1,346 @@
+//! Python bindings for using CHIP-8 games as reinforcement learning environments.
+//!
+//! Build with `maturin develop -m python/Cargo.toml`, then:
+//!
+//! ```python
+//! import numpy, chip8
+//!
+//! score = lambda env: env.registers[0xE]
+//! env = chip8.Env(open("rom/BRIX", "rb").read(), frameskip=4, reward=score)
+//! obs = env.reset()
+//! while True:
+//!     obs, reward, done = env.step(0x4)
+//!     pixels = numpy.asarray(obs)  # 32x64 array of 0 and 1
+//!     if done:
+//!         break
+//! ```
+
+use std::ffi::c_int;
+
+use chip_core::fault;
+use chip_core::input;
+use chip_core::machine;
+use chip_core::quirks;
+use chip_core::ram;
+use pyo3::exceptions::PyBufferError;
+use pyo3::exceptions::PyValueError;
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// Display width in pixels.
+const W: usize = 64;
+
+/// Display height in pixels.
+const H: usize = 32;
+
+/// Most ROM bytes that fit in memory after 0x200.
+const ROM_SIZE: usize = 0x1000 - 0x200;
+
+/// Observation shape, as rows and columns.
+static SHAPE: [isize; 2] = [H as isize, W as isize];
+
+/// Observation strides in bytes.
+static STRIDES: [isize; 2] = [W as isize, 1];
+
+/// Unsigned byte buffer format.
+static FORMAT: &[u8] = b"B\0";
+
+/// Snapshot of the framebuffer, exposing a read-only 32x64 byte buffer:
+/// 1 where a pixel is set, 0 where not.
+#[pyclass(frozen, module = "chip8")]
+pub struct Frame {
+    /// Pixels row by row from the top left
+    pixels: [u8; W * H],
+}
+
+#[pymethods]
+impl Frame {
+    /// Whether the pixel at `(x, y)` is set.
+    fn get(&self, x: usize, y: usize) -> bool {
+        x < W && y < H && self.pixels[y * W + x] > 0
+    }
+
+    fn __len__(&self) -> usize {
+        self.pixels.len()
+    }
+
+    fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.pixels)
+    }
+
+    unsafe fn __getbuffer__(slf: Bound<'_, Self>, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("View is null"));
+        }
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("Frame is read-only"));
+        }
+        let requested = |flag| flags & flag == flag;
+        (*view).buf = slf.get().pixels.as_ptr() as *mut _;
+        (*view).len = (W * H) as isize;
+        (*view).readonly = 1;
+        (*view).itemsize = 1;
+        (*view).format = if requested(ffi::PyBUF_FORMAT) { FORMAT.as_ptr() as *mut _ } else { std::ptr::null_mut() };
+        (*view).ndim = 2;
+        (*view).shape = if requested(ffi::PyBUF_ND) { SHAPE.as_ptr() as *mut _ } else { std::ptr::null_mut() };
+        (*view).strides = if requested(ffi::PyBUF_STRIDES) { STRIDES.as_ptr() as *mut _ } else { std::ptr::null_mut() };
+        (*view).suboffsets = std::ptr::null_mut();
+        (*view).internal = std::ptr::null_mut();
+        (*view).obj = slf.into_any().into_ptr();
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&self, _view: *mut ffi::Py_buffer) {}
+}
+
+/// Emulator state saved by `Env.save`, for restoring with `Env.restore`.
+#[pyclass(frozen, module = "chip8")]
+pub struct State {
+    /// Interpreter state
+    machine: machine::Machine,
+
+    /// Keypad and random number input
+    input: input::Input,
+
+    /// Frames run since the last reset
+    frames: u64,
+
+    /// Whether the episode was over
+    done: bool,
+
+    /// Key held down during the last step
+    held: Option<u8>,
+}
+
+/// CHIP-8 game as a gym-style environment.
+///
+/// Each `step` holds down one key (or none) for `frameskip` 60Hz frames of
+/// `cycles` instructions, and returns the observation, the reward computed by
+/// the `reward` hook, and whether the episode is over: when the `done` hook
+/// returns true, or when an instruction faults.
+#[pyclass(module = "chip8")]
+pub struct Env {
+    /// Interpreter state
+    machine: machine::Machine,
+
+    /// Keypad and random number input
+    input: input::Input,
+
+    /// State to go back to on `reset`
+    initial: (machine::Machine, input::Input),
+
+    /// Frames per step
+    frameskip: u32,
+
+    /// Instructions per frame
+    cycles: u32,
+
+    /// Frames run since the last reset
+    frames: u64,
+
+    /// Fault that ended the episode, if any
+    fault: Option<fault::Fault>,
+
+    /// Whether the episode is over
+    done: bool,
+
+    /// Key held down during the last step
+    held: Option<u8>,
+
+    /// Called with the environment after each step to compute the reward
+    reward: Option<Py<PyAny>>,
+
+    /// Called with the environment after each step to decide whether the episode is over
+    is_done: Option<Py<PyAny>>,
+}
+
+#[pymethods]
+impl Env {
+    /// Load `rom`, emulating the named `quirks` preset and seeding `RND` with `seed`.
+    #[new]
+    #[pyo3(signature = (rom, frameskip = 4, cycles = 10, seed = 0, quirks = "default", reward = None, done = None))]
+    fn new(
+        rom: &[u8],
+        frameskip: u32,
+        cycles: u32,
+        seed: u32,
+        quirks: &str,
+        reward: Option<Py<PyAny>>,
+        done: Option<Py<PyAny>>,
+    ) -> PyResult<Self> {
+        if rom.len() > ROM_SIZE {
+            return Err(PyValueError::new_err(format!("ROM is {} bytes, but at most {} fit in memory", rom.len(), ROM_SIZE)));
+        }
+        let quirks = quirks::Quirks::preset(quirks).map_err(|error| PyValueError::new_err(error.to_string()))?;
+        let options = machine::Options { quirks, ..machine::Options::default() };
+        let machine = machine::Machine::new(rom.iter().cloned(), options);
+        let input = input::Input::new(seed);
+        Ok(Env {
+            machine: machine.clone(),
+            input,
+            initial: (machine, input),
+            frameskip: frameskip.max(1),
+            cycles,
+            frames: 0,
+            fault: None,
+            done: false,
+            held: None,
+            reward,
+            is_done: done,
+        })
+    }
+
+    /// Go back to the state just after loading the ROM, and return the first observation.
+    fn reset(&mut self) -> Frame {
+        let (machine, input) = self.initial.clone();
+        self.machine = machine;
+        self.input = input;
+        self.frames = 0;
+        self.fault = None;
+        self.done = false;
+        self.held = None;
+        self.observe()
+    }
+
+    /// Hold down CHIP-8 key `action` (or no key if `None`) for `frameskip` frames,
+    /// and return `(observation, reward, done)`.
+    #[pyo3(signature = (action = None))]
+    fn step(slf: &Bound<'_, Self>, action: Option<u8>) -> PyResult<(Frame, f64, bool)> {
+        let (reward, is_done) = {
+            let mut env = slf.borrow_mut();
+            if let Some(action) = action.filter(|&action| action > 0xF) {
+                return Err(PyValueError::new_err(format!("Action {} is not a key from 0x0 to 0xF", action)));
+            }
+            env.run(action);
+            let env = &*env;
+            let clone = |hook: &Option<Py<PyAny>>| hook.as_ref().map(|hook| hook.clone_ref(slf.py()));
+            (clone(&env.reward), clone(&env.is_done))
+        };
+
+        let reward = match reward {
+        | Some(hook) => hook.call1(slf.py(), (slf,))?.extract::<f64>(slf.py())?,
+        | None => 0.0,
+        };
+        let done = match is_done {
+        | Some(hook) => hook.call1(slf.py(), (slf,))?.is_truthy(slf.py())?,
+        | None => false,
+        };
+
+        let mut env = slf.borrow_mut();
+        env.done |= done;
+        Ok((env.observe(), reward, env.done))
+    }
+
+    /// Snapshot the emulator, for restoring with `restore`.
+    fn save(&self) -> State {
+        State {
+            machine: self.machine.clone(),
+            input: self.input,
+            frames: self.frames,
+            done: self.done,
+            held: self.held,
+        }
+    }
+
+    /// Go back to a snapshot taken by `save`.
+    fn restore(&mut self, state: &State) {
+        self.machine = state.machine.clone();
+        self.input = state.input;
+        self.frames = state.frames;
+        self.done = state.done;
+        self.fault = None;
+        self.held = state.held;
+    }
+
+    /// Current observation, without stepping.
+    fn observe(&self) -> Frame {
+        Frame { pixels: self.machine.display.bytes() }
+    }
+
+    /// Frames held per step.
+    #[getter]
+    fn frameskip(&self) -> u32 {
+        self.frameskip
+    }
+
+    #[setter]
+    fn set_frameskip(&mut self, frameskip: u32) {
+        self.frameskip = frameskip.max(1);
+    }
+
+    /// Frames run since the last reset.
+    #[getter]
+    fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Registers V0 through VF.
+    #[getter]
+    fn registers(&self) -> Vec<u8> {
+        self.machine.cpu.reg.to_vec()
+    }
+
+    /// Program counter.
+    #[getter]
+    fn pc(&self) -> u16 {
+        self.machine.cpu.pc.into()
+    }
+
+    /// Index register I.
+    #[getter]
+    fn i(&self) -> u16 {
+        self.machine.cpu.idx.into()
+    }
+
+    /// Whether the sound timer is running.
+    #[getter]
+    fn sound(&self) -> bool {
+        self.machine.cpu.st > 0
+    }
+
+    /// Message of the fault that ended the episode, if any.
+    #[getter]
+    fn fault(&self) -> Option<String> {
+        self.fault.map(|fault| fault.to_string())
+    }
+
+    /// `len` bytes of memory starting at `addr`, wrapping around past 0xFFF.
+    fn memory(&self, addr: u16, len: u16) -> Vec<u8> {
+        (0..len)
+            .map(|offset| self.machine.ram[ram::Addr::from(addr & 0xFFF) + offset])
+            .collect()
+    }
+}
+
+impl Env {
+    /// Run `frameskip` frames holding down `action`, stopping at the first fault.
+    fn run(&mut self, action: Option<u8>) {
+        if self.done { return }
+        if action != self.held {
+            if let Some(key) = self.held { self.input.release(key); }
+            if let Some(key) = action { self.input.press(key); }
+            self.held = action;
+        }
+        for _ in 0..self.frameskip {
+            for _ in 0..self.cycles {
+                if let Err(fault) = self.machine.step(&mut self.input, &mut ()) {
+                    self.fault = Some(fault);
+                    self.done = true;
+                    return;
+                }
+            }
+            self.machine.tick();
+            self.frames += 1;
+        }
+    }
+}
+
+/// CHIP-8 games as reinforcement learning environments.
+#[pymodule]
+pub fn chip8(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<Env>()?;
+    module.add_class::<Frame>()?;
+    module.add_class::<State>()?;
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Drives the environment from Python code, as a training loop would.
+
+use std::ffi::CString;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Run `code` with the `chip8` module imported, failing the test on any exception.
+fn run(code: &str) {
+    Python::attach(|py| {
+        let module = pyo3::wrap_pymodule!(chip8::chip8)(py);
+        let globals = PyDict::new(py);
+        globals.set_item("chip8", module).unwrap();
+        let code = CString::new(code).unwrap();
+        if let Err(error) = py.run(&code, Some(&globals), None) {
+            error.display(py);
+            panic!("{}", error);
+        }
+    });
+}
+
+/// Each frame, clear the display, and if key 5 is held, increment V0 and
+/// draw the font sprite for 0 at (0, 0). Waits on the delay timer between frames.
+const ROM: &str = "bytes([
+    0x61, 0x05, 0x62, 0x00, 0x00, 0xE0, 0xE1, 0xA1, 0x12, 0x0C, 0x12, 0x04, 0x70, 0x01, 0xA0, 0x00,
+    0xD2, 0x25, 0x63, 0x01, 0xF3, 0x15, 0xF3, 0x07, 0x33, 0x00, 0x12, 0x16, 0x12, 0x04,
+])";
+
+#[test]
+fn observation() {
+    run(&format!(r#"
+env = chip8.Env({}, frameskip=1, cycles=20)
+obs = env.reset()
+assert len(obs) == 64 * 32 and bytes(obs) == bytes(64 * 32)
+
+obs, reward, done = env.step(0x5)
+view = memoryview(obs)
+assert view.shape == (32, 64) and view.format == "B" and view.readonly
+assert view[0, 0] == 1 and view[1, 0] == 1 and view[1, 1] == 0 and view[1, 3] == 1
+assert sum(bytes(obs)) == 14 and obs.get(3, 1) and not obs.get(1, 1)
+assert (reward, done) == (0.0, False)
+
+obs, _, _ = env.step(None)
+assert sum(bytes(obs)) == 0
+"#, ROM));
+}
+
+#[test]
+fn hooks() {
+    run(&format!(r#"
+env = chip8.Env({}, frameskip=3, cycles=20, reward=lambda env: env.registers[0], done=lambda env: env.registers[0] >= 6)
+env.reset()
+assert env.step(0x5)[1:] == (3.0, False)
+assert env.step(0x5)[1:] == (6.0, True)
+assert env.frames == 6
+assert env.step(0x5)[1:] == (6.0, True) and env.frames == 6
+env.reset()
+assert env.frames == 0 and env.registers[0] == 0
+assert env.step(0x5)[1:] == (3.0, False)
+"#, ROM));
+}
+
+#[test]
+fn save_restore() {
+    run(&format!(r#"
+env = chip8.Env({}, frameskip=2, cycles=20)
+env.step(0x5)
+state = env.save()
+before = bytes(env.observe()), env.registers, env.frames
+env.step(None)
+after = bytes(env.observe()), env.registers, env.frames
+env.step(0x5)
+assert (bytes(env.observe()), env.registers, env.frames) != before
+env.restore(state)
+assert (bytes(env.observe()), env.registers, env.frames) == before
+
+# Key 5 was held when saved, so stepping with no key releases it again
+env.step(None)
+assert (bytes(env.observe()), env.registers, env.frames) == after
+"#, ROM));
+}
+
+#[test]
+fn faults() {
+    run(r#"
+env = chip8.Env(bytes([0x00, 0xEE]))
+obs, reward, done = env.step()
+assert done and env.fault == "Stack underflow at 0x200"
+
+for args in [dict(rom=bytes(0xE01)), dict(rom=b"", quirks="nope")]:
+    try:
+        chip8.Env(**args)
+    except ValueError:
+        pass
+    else:
+        assert False, args
+
+try:
+    chip8.Env(b"").step(16)
+except ValueError:
+    pass
+else:
+    assert False
+"#);
+}
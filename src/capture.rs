@@ -0,0 +1,196 @@
+use std::fs;
+use std::io;
+use std::path;
+
+use crate::chip;
+use crate::color;
+use crate::display;
+
+/// Image colors used when the palette leaves them to the terminal.
+const WHITE: color::Rgb = color::Rgb(0xFF, 0xFF, 0xFF);
+const BLACK: color::Rgb = color::Rgb(0x00, 0x00, 0x00);
+
+/// Frames per second of the display.
+const FPS: u32 = 60;
+
+/// Largest image scale, keeping frames within the 65535 pixels a GIF allows.
+pub const MAX_SCALE: u32 = u16::MAX as u32 / display::W as u32;
+
+/// Scaled, two-color image of a display frame.
+struct Image {
+    /// Pixel scale factor
+    scale: u32,
+
+    /// Background and foreground colors
+    colors: [color::Rgb; 2],
+}
+
+impl Image {
+    fn new(scale: u32, palette: color::Palette) -> io::Result<Self> {
+        if scale > MAX_SCALE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Image scale {} is larger than the maximum of {}", scale, MAX_SCALE),
+            ));
+        }
+        Ok(Image {
+            scale: scale.max(1),
+            colors: [palette.bg.unwrap_or(BLACK), palette.fg.unwrap_or(WHITE)],
+        })
+    }
+
+    fn width(&self) -> u32 {
+        display::W as u32 * self.scale
+    }
+
+    fn height(&self) -> u32 {
+        display::H as u32 * self.scale
+    }
+
+    /// One byte per image pixel: 1 if set, 0 if unset.
+    fn indices(&self, chip: &chip::Chip) -> Vec<u8> {
+        let mut indices = Vec::with_capacity((self.width() * self.height()) as usize);
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                indices.push(chip.pixel((x / self.scale) as u8, (y / self.scale) as u8) as u8);
+            }
+        }
+        indices
+    }
+
+    /// Colors as a flat RGB palette, indexed by `indices`.
+    fn palette(&self) -> Vec<u8> {
+        self.colors.iter().flat_map(|&color::Rgb(r, g, b)| [r, g, b]).collect()
+    }
+
+    fn write_png<W: io::Write>(&self, chip: &chip::Chip, out: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width(), self.height());
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(self.palette());
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer.write_image_data(&self.indices(chip)).map_err(io::Error::other)
+    }
+
+    /// Write a plain PBM, where 1 is black: set pixels are 1 if the
+    /// foreground is darker than the background.
+    fn write_pbm<W: io::Write>(&self, chip: &chip::Chip, mut out: W) -> io::Result<()> {
+        let luma = |color::Rgb(r, g, b): color::Rgb| r as u32 * 299 + g as u32 * 587 + b as u32 * 114;
+        let dark = (luma(self.colors[1]) < luma(self.colors[0])) as u8;
+        writeln!(out, "P1")?;
+        writeln!(out, "{} {}", self.width(), self.height())?;
+        for row in self.indices(chip).chunks(self.width() as usize) {
+            let row = row
+                .iter()
+                .map(|&index| if index == dark { "1" } else { "0" })
+                .collect::<Vec<_>>();
+            writeln!(out, "{}", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Save the current display of `chip` to `path`, scaled up by `scale`,
+/// as a PBM if the extension is `.pbm` and as a PNG otherwise.
+pub fn screenshot(chip: &chip::Chip, path: &path::Path, scale: u32, palette: color::Palette) -> io::Result<()> {
+    let image = Image::new(scale, palette)?;
+    let file = io::BufWriter::new(fs::File::create(path)?);
+    match path.extension().and_then(|extension| extension.to_str()) {
+    | Some("pbm") => image.write_pbm(chip, file),
+    | _ => image.write_png(chip, file),
+    }
+}
+
+/// Where recorded frames go.
+enum Sink {
+    /// Animated GIF, with the latest frame held back until it changes
+    Gif {
+        encoder: gif::Encoder<io::BufWriter<fs::File>>,
+        pending: Option<(Vec<u8>, u32)>,
+    },
+
+    /// Numbered PNG files in a directory
+    Frames { dir: path::PathBuf },
+}
+
+/// Records display frames into an animated GIF or a sequence of PNG files.
+pub struct Recorder {
+    image: Image,
+    sink: Sink,
+
+    /// Frames recorded so far
+    frame: u32,
+}
+
+impl Recorder {
+    /// Record into an animated GIF if `path` ends in `.gif`, and otherwise
+    /// into PNG files named `00000.png`, `00001.png`, etc. in directory `path`.
+    pub fn create(path: &path::Path, scale: u32, palette: color::Palette) -> io::Result<Self> {
+        let image = Image::new(scale, palette)?;
+        let sink = match path.extension().and_then(|extension| extension.to_str()) {
+        | Some("gif") => {
+            let file = io::BufWriter::new(fs::File::create(path)?);
+            let (width, height) = (image.width() as u16, image.height() as u16);
+            let mut encoder = gif::Encoder::new(file, width, height, &image.palette()).map_err(io::Error::other)?;
+            encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+            Sink::Gif { encoder, pending: None }
+        }
+        | _ => {
+            fs::create_dir_all(path)?;
+            Sink::Frames { dir: path.to_path_buf() }
+        }
+        };
+        Ok(Recorder { image, sink, frame: 0 })
+    }
+
+    /// Record the current display of `chip` as the next 60Hz frame.
+    pub fn frame(&mut self, chip: &chip::Chip) -> io::Result<()> {
+        match &mut self.sink {
+        | Sink::Frames { dir } => {
+            let file = io::BufWriter::new(fs::File::create(dir.join(format!("{:05}.png", self.frame)))?);
+            self.image.write_png(chip, file)?;
+        }
+        | Sink::Gif { encoder, pending } => {
+            let indices = self.image.indices(chip);
+            match pending {
+            | Some((last, _)) if *last == indices => (),
+            | _ => {
+                if let Some((last, start)) = pending.replace((indices, self.frame)) {
+                    write_gif_frame(encoder, &self.image, &last, start, self.frame)?;
+                }
+            }
+            }
+        }
+        }
+        self.frame += 1;
+        Ok(())
+    }
+
+    /// Write any frame still held back and finish the recording.
+    pub fn finish(mut self) -> io::Result<()> {
+        if let Sink::Gif { encoder, pending: Some((last, start)) } = &mut self.sink {
+            write_gif_frame(encoder, &self.image, last, *start, self.frame.max(*start + 1))?;
+        }
+        Ok(())
+    }
+}
+
+/// Write a GIF frame shown from 60Hz frame `start` until `end`, rounding
+/// to the GIF's 10ms delay units without accumulating error.
+fn write_gif_frame<W: io::Write>(
+    encoder: &mut gif::Encoder<W>,
+    image: &Image,
+    indices: &[u8],
+    start: u32,
+    end: u32,
+) -> io::Result<()> {
+    let centis = |frame: u32| (frame * 100 + FPS / 2) / FPS;
+    let frame = gif::Frame {
+        width: image.width() as u16,
+        height: image.height() as u16,
+        delay: (centis(end) - centis(start)).min(u16::MAX as u32) as u16,
+        buffer: std::borrow::Cow::Borrowed(indices),
+        ..gif::Frame::default()
+    };
+    encoder.write_frame(&frame).map_err(io::Error::other)
+}
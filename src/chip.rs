@@ -1,283 +1,582 @@
+use std::collections::HashSet;
+use std::io::Write;
+
 use termion::cursor;
 use termion::event;
+use termion::style;
 
 use crate::cpu;
 use crate::asm;
 use crate::ram;
-use crate::stack;
 use crate::display;
+use crate::input;
+use crate::machine;
+use crate::color;
+use crate::keypad;
+use crate::layout;
+use crate::fault;
+use crate::quirks;
+use crate::trace;
+
+/// CHIP-8 keys in keypad order.
+const KEYPAD: [[u8; 4]; 4] = [
+    [0x1, 0x2, 0x3, 0xC],
+    [0x4, 0x5, 0x6, 0xD],
+    [0x7, 0x8, 0x9, 0xE],
+    [0xA, 0x0, 0xB, 0xF],
+];
+
+/// What a terminal cell of the UI shows, e.g. under a mouse click.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Hit {
+    /// CHIP-8 key in the keypad panel
+    Key(u8),
+
+    /// Instruction address in the disassembly panel
+    Addr(ram::Addr),
+}
+
+/// Emulator configuration.
+#[derive(Copy, Clone, Debug)]
+pub struct Options {
+    /// How to handle stack and memory faults
+    pub fault: fault::Policy,
+
+    /// Memory address where font data is stored
+    pub font: ram::Addr,
+
+    /// Memory address where programs are loaded and execution starts
+    pub load: ram::Addr,
+
+    /// Seed for `RND`, or `None` to seed from system entropy
+    pub seed: Option<u64>,
+
+    /// Instructions executed per 60Hz frame by `Chip::frame`
+    pub cycles: u32,
+
+    /// Behaviors that differ between interpreters
+    pub quirks: quirks::Quirks,
+
+    /// Keyboard keys bound to CHIP-8 keys
+    pub keymap: keypad::Keymap,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            fault: fault::Policy::default(),
+            font: ram::FONT_OFFSET,
+            load: ram::PROGRAM_OFFSET,
+            seed: None,
+            cycles: 10,
+            quirks: quirks::Quirks::default(),
+            keymap: keypad::Keymap::default(),
+        }
+    }
+}
+
+impl Options {
+    /// Configuration of the interpreter core.
+    fn machine(&self) -> machine::Options {
+        machine::Options {
+            fault: self.fault,
+            font: self.font,
+            load: self.load,
+            quirks: self.quirks,
+        }
+    }
+}
+
+/// Keypad and random number input, recording keypad activity for the keypad panel.
+struct Host<'a> {
+    /// Keypad and random number input
+    input: &'a mut input::Input,
+
+    /// Recently pressed and polled keys
+    activity: &'a mut keypad::Activity,
+}
+
+impl machine::Host for Host<'_> {
+    fn random(&mut self) -> u8 {
+        self.input.random()
+    }
+
+    fn pressed(&mut self, key: u8) -> bool {
+        self.activity.poll(key);
+        self.input.pressed(key)
+    }
+
+    fn key(&mut self) -> Option<u8> {
+        let key = self.input.key();
+        if key.is_none() { self.activity.wait(); }
+        key
+    }
+}
 
 /// Main emulator logic.
 #[derive(Clone)]
 pub struct Chip {
-    /// CHIP-8 CPU
-    cpu: cpu::CPU,
+    /// Interpreter state
+    pub(crate) machine: machine::Machine,
 
-    /// Random access memory
-    ram: ram::Mem,
+    /// Keypad and random number input
+    input: input::Input,
 
-    /// Last-pressed key
-    key: Option<u8>,
+    /// Recently pressed and polled keys
+    activity: keypad::Activity,
 
-    /// Stack memory
-    stack: stack::Mem,
+    /// Addresses to pause at in the terminal debugger
+    breakpoints: HashSet<ram::Addr>,
 
-    /// Terminal-backed display
-    display: display::Display,
+    /// Terminal rendering of the display
+    screen: display::Screen,
+
+    /// Panel lines as last drawn to the terminal
+    presented: Vec<(u16, u16, String)>,
+
+    /// Emulator configuration
+    pub(crate) options: Options,
 }
 
 impl Chip {
     /// Create a new emulator running `program`.
     pub fn new<B>(program: B) -> Self where B: IntoIterator<Item = u8> {
-        Chip {
-            cpu: cpu::CPU::default(),
-            ram: ram::Mem::new(program),
-            key: None,
-            stack: stack::Mem::default(),
-            display: display::Display::default(),
+        Self::with_options(program, Options::default())
+    }
+
+    /// Create a new emulator running `program` configured by `options`.
+    ///
+    /// Panics if the program overlaps the font data; see `Chip::try_with_options`.
+    pub fn with_options<B>(program: B, options: Options) -> Self where B: IntoIterator<Item = u8> {
+        Self::try_with_options(program, options).unwrap_or_else(|overlap| panic!("{}", overlap))
+    }
+
+    /// Create a new emulator running `program` configured by `options`,
+    /// unless the program overlaps the font data.
+    pub fn try_with_options<B>(program: B, options: Options) -> Result<Self, ram::Overlap> where B: IntoIterator<Item = u8> {
+        let seed = options.seed.unwrap_or_else(rand::random);
+        Ok(Chip {
+            machine: machine::Machine::try_new(program, options.machine())?,
+            input: input::Input::new((seed ^ seed >> 32) as u32),
+            activity: keypad::Activity::default(),
+            breakpoints: HashSet::new(),
+            screen: display::Screen::default(),
+            presented: Vec::new(),
+            options,
+        })
+    }
+
+    /// Configure a new emulator.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Emulator configuration.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Registers and timers.
+    pub fn cpu(&self) -> &cpu::CPU {
+        &self.machine.cpu
+    }
+
+    /// Memory, including the font and program.
+    pub fn mem(&self) -> &ram::Mem {
+        &self.machine.ram
+    }
+
+    /// Return addresses on the stack, from bottom to top.
+    pub fn stack(&self) -> &[ram::Addr] {
+        self.machine.stack.frames(self.machine.cpu.sp)
+    }
+
+    /// Display pixels.
+    pub fn display(&self) -> &display::Display {
+        &self.machine.display
+    }
+
+    /// Execute one 60Hz frame: `Options::cycles` instructions, then a `tick`.
+    /// Stops without ticking at the first fault.
+    pub fn frame(&mut self) -> Result<(), fault::Fault> {
+        self.frame_traced(&mut ())
+    }
+
+    /// Execute one 60Hz frame, reporting memory accesses to `trace`.
+    pub fn frame_traced<T: trace::Trace>(&mut self, trace: &mut T) -> Result<(), fault::Fault> {
+        for _ in 0..self.options.cycles {
+            self.step_traced(trace)?;
         }
+        self.tick();
+        Ok(())
     }
 
-    /// Decrement the sound and delay timers.
+    /// Decrement the sound and delay timers, and advance the display one frame,
+    /// without executing any instructions.
     pub fn tick(&mut self) {
-        self.cpu.st = self.cpu.st.saturating_sub(1);
-        self.cpu.dt = self.cpu.dt.saturating_sub(1);
+        self.machine.tick();
+        self.screen.frame();
+        self.activity.tick();
     }
 
     /// Update the latest pressed key.
     pub fn set_key(&mut self, event: event::Key) {
-        use event::Key::*;
-        self.key = match event {
-        | Char('1') => Some(0x01),
-        | Char('2') => Some(0x02),
-        | Char('3') => Some(0x03),
-        | Char('4') => Some(0x0C),
-        | Char('q') => Some(0x04),
-        | Char('w') => Some(0x05),
-        | Char('e') => Some(0x06),
-        | Char('r') => Some(0x0D),
-        | Char('a') => Some(0x07),
-        | Char('s') => Some(0x08),
-        | Char('d') => Some(0x09),
-        | Char('f') => Some(0x0E),
-        | Char('z') => Some(0x0A),
-        | Char('x') => Some(0x00),
-        | Char('c') => Some(0x0B),
-        | Char('v') => Some(0x0F),
-        | _ => None,
-        };
+        if let event::Key::Char(c) = event {
+            if let Some(key) = self.options.keymap.key(c) {
+                self.input.tap(key);
+                self.activity.press(key);
+            }
+        }
     }
 
-    /// Execute a single CPU cycle.
-    pub fn step(&mut self) {
+    /// Hold down CHIP-8 `key` until released, e.g. with the mouse.
+    pub fn press(&mut self, key: u8) {
+        self.input.press(key);
+        self.activity.press(key & 0xF);
+    }
 
-        let hi = self.ram[self.cpu.pc];
-        let lo = self.ram[self.cpu.pc + 1];
-        let op = asm::Asm::parse(hi, lo).unwrap();
+    /// Release CHIP-8 `key`.
+    pub fn release(&mut self, key: u8) {
+        self.input.release(key);
+    }
 
-        self.cpu.pc += 2;
+    /// Whether CHIP-8 `key` is held down.
+    pub fn is_held(&self, key: u8) -> bool {
+        self.input.is_held(key)
+    }
 
-        use asm::Asm::*;
+    /// Address of the next instruction.
+    pub fn pc(&self) -> ram::Addr {
+        self.machine.cpu.pc
+    }
 
-        match op {
-        | SYS(_) => (),
-        | CLS => {
-            self.display.clear();
-        }
-        | RET => {
-            self.cpu.sp.dec();
-            self.cpu.pc = self.stack[self.cpu.sp];
-        }
-        | JP(addr) => {
-            self.cpu.pc = addr;
-        }
-        | CALL(addr) => {
-            self.stack[self.cpu.sp] = self.cpu.pc;
-            self.cpu.sp.inc();
-            self.cpu.pc = addr;
-        }
-        | SEC(x, kk) => {
-            if self.cpu[x] == kk { self.cpu.pc += 2; }
-        }
-        | SNEC(x, kk) => {
-            if self.cpu[x] != kk { self.cpu.pc += 2; }
-        }
-        | SER(x, y) => {
-            if self.cpu[x] == self.cpu[y] { self.cpu.pc += 2; }
-        }
-        | SNER(x, y) => {
-            if self.cpu[x] != self.cpu[y] { self.cpu.pc += 2; }
-        }
-        | LDC(x, kk) => {
-            self.cpu[x] = kk;
-        }
-        | ADDC(x, kk) => {
-            self.cpu[x] += kk;;
-        }
-        | LDR(x, y) => {
-            self.cpu[x] = self.cpu[y];
-        }
-        | OR(x, y) => {
-            self.cpu[x] |= self.cpu[y];
-        }
-        | AND(x, y) => {
-            self.cpu[x] &= self.cpu[y];
-        }
-        | XOR(x, y) => {
-            self.cpu[x] ^= self.cpu[y];
-        }
-        | ADDR(x, y) => {
-            let z = self.cpu[x] as u16 + self.cpu[y] as u16;
-            self.cpu[cpu::VF] = if z > 0xFF { 1 } else { 0 };
-            self.cpu[x] = z as u8;
-        }
-        | SUB(x, y) => {
-            let vx = self.cpu[x];
-            let vy = self.cpu[y];
-            self.cpu[cpu::VF] = if vx > vy { 1 } else { 0 };
-            self.cpu[x] = vx.wrapping_sub(vy);
-        }
-        | SHR(x) => {
-            let vx = self.cpu[x];
-            self.cpu[cpu::VF] = vx & 0x01;
-            self.cpu[x] = vx >> 0x01;
-        }
-        | SUBN(x, y) => {
-            let vx = self.cpu[x];
-            let vy = self.cpu[y];
-            self.cpu[cpu::VF] = if vy > vx { 1 } else { 0 };
-            self.cpu[x] = vy.wrapping_sub(vx);
-        }
-        | SHL(x) => {
-            let vx = self.cpu[x];
-            self.cpu[cpu::VF] = if vx & 0x80 != 0x00 { 1 } else { 0 };
-            self.cpu[x] = vx << 0x01;
-        }
-        | LDI(addr) => {
-            self.cpu.idx = addr;
-        }
-        | JO(addr) => {
-            self.cpu.pc = addr + self.cpu[cpu::V0] as u16;
-        }
-        | RND(x, kk) => {
-            self.cpu[x] = rand::random::<u8>() & kk;
-        }
-        | DRW(x, y, n) => {
-            self.cpu[cpu::VF] = 0;
-            let vx = self.cpu[x];
-            let vy = self.cpu[y];
-            for dy in 0..n {
-                let mut line = self.ram[self.cpu.idx + dy as u16];
-                for dx in 0..8 {
-                    if line & 0x80 > 0 { self.cpu[cpu::VF] |= self.display.toggle(vx + dx, vy + dy); }
-                    line <<= 1;
+    /// Set or clear a breakpoint at `addr`, returning whether it is now set.
+    pub fn toggle_breakpoint(&mut self, addr: ram::Addr) -> bool {
+        if self.breakpoints.remove(&addr) { false } else { self.breakpoints.insert(addr) }
+    }
+
+    /// Breakpoints set in the terminal debugger.
+    pub fn breakpoints(&self) -> &HashSet<ram::Addr> {
+        &self.breakpoints
+    }
+
+    /// Execute a single instruction, without advancing the timers.
+    pub fn step(&mut self) -> Result<(), fault::Fault> {
+        self.step_traced(&mut ())
+    }
+
+    /// Execute a single instruction, reporting memory accesses to `trace`.
+    ///
+    /// Unless the fault policy is `Policy::Wrap`, a faulting instruction
+    /// has no effect and the program counter is left pointing at it.
+    /// Invalid instructions always fault this way.
+    pub fn step_traced<T: trace::Trace>(&mut self, trace: &mut T) -> Result<(), fault::Fault> {
+        let before = *self.machine.display.rows();
+        let mut host = Host { input: &mut self.input, activity: &mut self.activity };
+        let result = self.machine.step(&mut host, trace);
+        self.screen.fade(&before, self.machine.display.rows());
+        result
+    }
+
+    /// Whether the display pixel at `(x, y)` is set. Coordinates off the display are unset.
+    pub fn pixel(&self, x: u8, y: u8) -> bool {
+        self.machine.display.get(x, y)
+    }
+
+    /// Change how the display is rendered.
+    pub fn set_render(&mut self, render: display::Render) {
+        self.screen.set_render(render);
+        self.presented.clear();
+    }
+
+    /// Keep unset pixels visible for `frames` ticks with dimmer shades.
+    pub fn set_persist(&mut self, frames: u8) {
+        self.screen.set_persist(frames);
+    }
+
+    /// Change the display's pixel colors, as supported by the terminal.
+    pub fn set_palette(&mut self, palette: color::Palette, depth: color::Depth) {
+        self.screen.set_palette(palette, depth);
+        self.presented.clear();
+    }
+
+    /// Draw the current display and panels placed by `layout`, buffered into
+    /// a single write to `out`. Only cells and lines that changed since the
+    /// last draw are written.
+    pub fn draw<W: std::io::Write>(&mut self, layout: &layout::Layout, out: &mut W) -> std::io::Result<()> {
+        let placement = layout.place(self.screen.size());
+        let mut buffer = Vec::new();
+        let (dx, dy) = placement.display;
+        self.screen.draw(&self.machine.display, dx, dy, &mut buffer)?;
+        let lines = placement.panels
+            .iter()
+            .flat_map(|&(panel, rect)| self.panel(panel, rect))
+            .collect::<Vec<_>>();
+        for (index, entry) in lines.iter().enumerate() {
+            if self.presented.get(index) == Some(entry) { continue }
+            let (x, y, line) = entry;
+            write!(buffer, "{}{}", cursor::Goto(*x, *y), line)?;
+        }
+        self.presented = lines;
+        out.write_all(&buffer)
+    }
+
+    /// Forget what was last drawn, so the next draw redraws everything.
+    pub fn invalidate(&mut self) {
+        self.screen.invalidate();
+        self.presented.clear();
+    }
+
+    /// Lay out the lines of `panel` in `rect`, padded to its width.
+    fn panel(&self, panel: layout::Panel, rect: layout::Rect) -> Vec<(u16, u16, String)> {
+        let lines = match panel {
+        | layout::Panel::Registers => self.registers(),
+        | layout::Panel::Disassembly => self.disassembly(rect.h),
+        | layout::Panel::Memory => self.memory(rect.h),
+        | layout::Panel::Stack => self.frames(),
+        | layout::Panel::Keypad => self.keypad(),
+        };
+        lines
+            .into_iter()
+            .chain(std::iter::repeat(String::new()))
+            .take(rect.h as usize)
+            .enumerate()
+            .map(|(dy, line)| (rect.x + 1, rect.y + dy as u16 + 1, fit(&line, rect.w as usize)))
+            .collect()
+    }
+
+    /// Registers, followed by the program counter, stack pointer, timers, and I.
+    fn registers(&self) -> Vec<String> {
+        let mut lines = (0x0..=0xF)
+            .step_by(4)
+            .map(|offset| {
+                let registers = (offset..offset + 4)
+                    .map(|offset| cpu::V0 + offset)
+                    .map(|x| format!("{}: {:#04X}        ", x, self.machine.cpu[x]))
+                    .collect::<String>();
+                format!("   {}", registers)
+            })
+            .collect::<Vec<_>>();
+        lines.push(format!(
+            "   PC: {}    SP: {}    ST: {:#04X}    DT: {:#04X}    I: {}",
+            self.machine.cpu.pc,
+            self.machine.cpu.sp,
+            self.machine.cpu.st,
+            self.machine.cpu.dt,
+            self.machine.cpu.idx
+        ));
+        lines
+    }
+
+    /// Offset from the program counter of the instruction on `row` of `rows`.
+    fn disassembly_offset(row: u16, rows: u16) -> i32 {
+        (row as i32 - rows as i32 / 2) * 2
+    }
+
+    /// `rows` instructions centered on the program counter, with breakpoints marked by `*`.
+    fn disassembly(&self, rows: u16) -> Vec<String> {
+        (0..rows)
+            .map(|row| Self::disassembly_offset(row, rows))
+            .map(|da| {
+                let op = self.machine.cpu.pc
+                    .offset(da)
+                    .and_then(|addr| Some((addr, asm::Asm::parse(self.machine.ram[addr], self.machine.ram[addr + 1])?)));
+                match op {
+                | None => String::new(),
+                | Some((addr, op)) => {
+                    let marker = match (self.breakpoints.contains(&addr), da == 0) {
+                    | (true, true) => "*-> ",
+                    | (false, true) => "--> ",
+                    | (true, false) => " *  ",
+                    | (false, false) => "    ",
+                    };
+                    format!("{}{}: {}", marker, addr, op)
                 }
-            }
-        }
-        | SKP(x) => {
-            match self.key.take() {
-            | Some(k) if k == self.cpu[x] => { self.cpu.pc += 2; },
-            | _ => (),
-            }
-        }
-        | SKNP(x) => {
-            match self.key.take() {
-            | None => { self.cpu.pc += 2; },
-            | Some(k) if k != self.cpu[x] => { self.cpu.pc += 2; },
-            | _ => (),
-            }
-        }
-        | LDTR(x) => {
-            self.cpu[x] = self.cpu.dt;
-        }
-        | LDK(x) => {
-            match self.key.take() {
-            | None => { self.cpu.pc -= 2; }
-            | Some(k) => { self.cpu[x] = k },
-            }
-        }
-        | LDRT(x) => {
-            self.cpu.dt = self.cpu[x];
-        }
-        | LDRS(x) => {
-            self.cpu.st = self.cpu[x];
-        }
-        | ADDI(x) => {
-            self.cpu.idx += self.cpu[x] as u16;
-        }
-        | LDS(x) => {
-            self.cpu.idx = ram::FONT_OFFSET + (self.cpu[x] as u16 * 5);
+                }
+            })
+            .collect()
+    }
+
+    /// Hex dump of `rows` lines of 8 bytes, starting two lines before I,
+    /// with the byte at I marked by `>`.
+    fn memory(&self, rows: u16) -> Vec<String> {
+        let start = (u16::from(self.machine.cpu.idx) / 8).saturating_sub(2) * 8;
+        (start..0x1000)
+            .step_by(8)
+            .take(rows as usize)
+            .map(|line| {
+                let bytes = (line..line + 8)
+                    .map(ram::Addr::from)
+                    .map(|addr| format!("{}{:02X}", if addr == self.machine.cpu.idx { '>' } else { ' ' }, self.machine.ram[addr]))
+                    .collect::<String>();
+                format!("{}:{}", ram::Addr::from(line), bytes)
+            })
+            .collect()
+    }
+
+    /// Return addresses on the stack, oldest first.
+    fn frames(&self) -> Vec<String> {
+        self.machine.stack
+            .frames(self.machine.cpu.sp)
+            .iter()
+            .enumerate()
+            .map(|(depth, addr)| format!("{:>2}: {}", depth, addr))
+            .collect()
+    }
+
+    /// CHIP-8 keypad, with the keyboard key bound to each key. Keys recently
+    /// pressed are inverted, and keys recently tested by `SKP` or `SKNP` underlined.
+    fn keypad(&self) -> Vec<String> {
+        let mut lines = KEYPAD
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&key| {
+                        let pressed = self.activity.is_pressed(key) || self.input.is_held(key);
+                        let polled = self.activity.is_polled(key);
+                        format!(
+                            "{}{} {:X}:{} {}",
+                            if pressed { style::Invert.as_ref() } else { "" },
+                            if polled { style::Underline.as_ref() } else { "" },
+                            key,
+                            self.options.keymap.char(key),
+                            if pressed || polled { style::Reset.as_ref() } else { "" },
+                        )
+                    })
+                    .collect()
+            })
+            .collect::<Vec<String>>();
+        if self.activity.is_waiting() {
+            lines.push(String::from(" Waiting for key..."));
+        }
+        lines
+    }
+
+    /// What the terminal cell at 1-based `(x, y)` shows, as placed by `layout`.
+    pub fn hit(&self, layout: &layout::Layout, x: u16, y: u16) -> Option<Hit> {
+        let (x, y) = (x.checked_sub(1)?, y.checked_sub(1)?);
+        let (panel, rect) = layout
+            .place(self.screen.size())
+            .panels
+            .into_iter()
+            .find(|(_, rect)| rect.x <= x && x < rect.x + rect.w && rect.y <= y && y < rect.y + rect.h)?;
+        let (col, row) = (x - rect.x, y - rect.y);
+        match panel {
+        | layout::Panel::Keypad => KEYPAD.get(row as usize)?.get(col as usize / 5).cloned().map(Hit::Key),
+        | layout::Panel::Disassembly => self.machine.cpu.pc.offset(Self::disassembly_offset(row, rect.h)).map(Hit::Addr),
+        | _ => None,
         }
-        | LDB(x) => {
-            let vx = self.cpu[x];
-            self.ram[self.cpu.idx + 0] = (vx / 100) % 10;
-            self.ram[self.cpu.idx + 1] = (vx / 10) % 10;
-            self.ram[self.cpu.idx + 2] = (vx / 1) % 10;
+    }
+
+    /// Draw a status `message` on the line placed by `layout`.
+    pub fn message<W: std::io::Write>(&self, layout: &layout::Layout, message: &str, out: &mut W) -> std::io::Result<()> {
+        match layout.place(self.screen.size()).message {
+        | None => Ok(()),
+        | Some(rect) => {
+            let message = format!("{:<1$.1$}", message, rect.w as usize);
+            write!(out, "{}{}", cursor::Goto(rect.x + 1, rect.y + 1), message)
         }
-        | WR(x) => {
-            for offset in 0..=x {
-                self.ram[self.cpu.idx + offset as u16] = self.cpu[cpu::V0 + offset]; 
-            }
-            self.cpu.idx += x as u16 + 1;
         }
-        | RD(x) => {
-            for offset in 0..=x {
-                self.cpu[cpu::V0 + offset] = self.ram[self.cpu.idx + offset as u16];
+    }
+}
+
+/// Truncate or pad `line` to `width` columns, not counting escape sequences.
+fn fit(line: &str, width: usize) -> String {
+    let mut fitted = String::new();
+    let mut columns = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1B' {
+            fitted.push(c);
+            for c in chars.by_ref() {
+                fitted.push(c);
+                if c.is_ascii_alphabetic() { break }
             }
-            self.cpu.idx += x as u16 + 1;
+        } else if columns < width {
+            fitted.push(c);
+            columns += 1;
         }
-        };
     }
+    fitted.extend(std::iter::repeat_n(' ', width - columns));
+    fitted
+}
 
-    /// Draw the current display.
-    pub fn draw<W: std::io::Write>(&mut self, dx: u16, dy: u16, out: &mut W) -> std::io::Result<()> {
-        self.display.draw(dx, dy, out)?;
+/// Builder for a `Chip`, starting from the default `Options`.
+///
+/// ```
+/// use chip::{Chip, Quirks};
+///
+/// let mut chip = Chip::builder()
+///     .quirks(Quirks::preset("vip").unwrap())
+///     .seed(42)
+///     .build(vec![0x60, 0x2A]);
+///
+/// chip.step().unwrap();
+/// assert_eq!(chip.cpu().reg[0], 0x2A);
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Builder {
+    /// Options so far
+    options: Options,
+}
 
-        for offset in 0x0..=0xF {
-            if offset % 4 == 0 {
-                let dx = dx + 4;
-                let dy = dy + display::H as u16 + 1 + (offset as u16 / 2); 
-                write!(out, "{}", cursor::Goto(dx, dy))?;
-            }
-            let x = cpu::V0 + offset;
-            write!(out, "{}: {:#04X}        ", x, self.cpu[x])?;
-        }
+impl Builder {
+    /// Emulate interpreter `quirks`.
+    pub fn quirks(mut self, quirks: quirks::Quirks) -> Self {
+        self.options.quirks = quirks;
+        self
+    }
 
-        write!(
-            out,
-            "{}   PC: {}    SP: {}    ST: {:#04X}    DT: {:#04X}    I: {}",
-            cursor::Goto(dx, dy + display::H as u16 + 1 + 8),
-            self.cpu.pc,
-            self.cpu.sp,
-            self.cpu.st,
-            self.cpu.dt,
-            self.cpu.idx
-        )?;
-        
-        let dx = dx + display::W as u16 + 1;
-        let dy = dy + 1;
-
-        for (dy, da) in (-10..=10).enumerate().map(|(y, da)| (y as u16 * 2 + dy, da * 2)) {
-
-            write!(out, "{}", cursor::Goto(dx, dy))?;
-
-            let addr = match self.cpu.pc.offset(da) {
-            | Some(addr) => addr,
-            | None => continue,
-            };
-
-            let op = match asm::Asm::parse(self.ram[addr], self.ram[addr + 1]) {
-            | Some(op) => op,
-            | None => continue,
-            };
-
-            if da == 0 {
-                write!(out, "--> {}: {}          ", addr, op)?;
-            } else {
-                write!(out, "    {}: {}          ", addr, op)?;
-            }
-        }
+    /// Store font data at `addr`.
+    pub fn font(mut self, addr: ram::Addr) -> Self {
+        self.options.font = addr;
+        self
+    }
 
-        Ok(())
+    /// Load the program at `addr` and start executing there, e.g. 0x600 for ETI 660 programs.
+    pub fn load(mut self, addr: ram::Addr) -> Self {
+        self.options.load = addr;
+        self
+    }
+
+    /// Seed `RND` for reproducible runs.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.options.seed = Some(seed);
+        self
+    }
+
+    /// Handle faults with `policy`.
+    pub fn fault(mut self, policy: fault::Policy) -> Self {
+        self.options.fault = policy;
+        self
+    }
+
+    /// Execute `cycles` instructions per frame.
+    pub fn cycles(mut self, cycles: u32) -> Self {
+        self.options.cycles = cycles;
+        self
+    }
+
+    /// Bind keyboard keys to CHIP-8 keys with `keymap`.
+    pub fn keymap(mut self, keymap: keypad::Keymap) -> Self {
+        self.options.keymap = keymap;
+        self
+    }
+
+    /// Options built so far.
+    pub fn options(&self) -> Options {
+        self.options
+    }
+
+    /// Create the emulator running `program`.
+    ///
+    /// Panics if the program overlaps the font data; see `Builder::try_build`.
+    pub fn build<B>(self, program: B) -> Chip where B: IntoIterator<Item = u8> {
+        Chip::with_options(program, self.options)
+    }
+
+    /// Create the emulator running `program`, unless it overlaps the font data.
+    pub fn try_build<B>(self, program: B) -> Result<Chip, ram::Overlap> where B: IntoIterator<Item = u8> {
+        Chip::try_with_options(program, self.options)
     }
 }
@@ -0,0 +1,134 @@
+/// 24-bit color.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Mix `level / 255` of `other` into this color.
+    pub fn blend(self, other: Rgb, level: u8) -> Rgb {
+        let mix = |a: u8, b: u8| ((a as u16 * (255 - level) as u16 + b as u16 * level as u16) / 255) as u8;
+        Rgb(mix(self.0, other.0), mix(self.1, other.1), mix(self.2, other.2))
+    }
+
+    /// Nearest color in the 256-color palette's 6x6x6 cube or grayscale ramp.
+    fn ansi256(self) -> u8 {
+        let Rgb(r, g, b) = self;
+        let cube = |c: u8| if c < 48 { 0 } else if c < 115 { 1 } else { (c - 35) / 40 };
+        let level = |i: u8| if i == 0 { 0 } else { i * 40 + 55 };
+        let (cr, cg, cb) = (cube(r), cube(g), cube(b));
+        let average = (r as u16 + g as u16 + b as u16) / 3;
+        let gray = if average > 238 { 23 } else { average.saturating_sub(3) as u8 / 10 };
+        let (lr, lg, lb) = (level(cr), level(cg), level(cb));
+        let lgray = gray * 10 + 8;
+        if distance(self, Rgb(lgray, lgray, lgray)) < distance(self, Rgb(lr, lg, lb)) {
+            232 + gray
+        } else {
+            16 + 36 * cr + 6 * cg + cb
+        }
+    }
+
+    /// Nearest of the 16 standard terminal colors, using xterm's defaults.
+    fn ansi16(self) -> u8 {
+        const ANSI: [Rgb; 16] = [
+            Rgb(0x00, 0x00, 0x00), Rgb(0xCD, 0x00, 0x00), Rgb(0x00, 0xCD, 0x00), Rgb(0xCD, 0xCD, 0x00),
+            Rgb(0x00, 0x00, 0xEE), Rgb(0xCD, 0x00, 0xCD), Rgb(0x00, 0xCD, 0xCD), Rgb(0xE5, 0xE5, 0xE5),
+            Rgb(0x7F, 0x7F, 0x7F), Rgb(0xFF, 0x00, 0x00), Rgb(0x00, 0xFF, 0x00), Rgb(0xFF, 0xFF, 0x00),
+            Rgb(0x5C, 0x5C, 0xFF), Rgb(0xFF, 0x00, 0xFF), Rgb(0x00, 0xFF, 0xFF), Rgb(0xFF, 0xFF, 0xFF),
+        ];
+        (0..16).min_by_key(|&i| distance(self, ANSI[i as usize])).unwrap_or(0)
+    }
+}
+
+/// Squared Euclidean distance between two colors.
+fn distance(lhs: Rgb, rhs: Rgb) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(lhs.0, rhs.0) + d(lhs.1, rhs.1) + d(lhs.2, rhs.2)
+}
+
+impl std::str::FromStr for Rgb {
+    type Err = String;
+    fn from_str(rgb: &str) -> Result<Self, Self::Err> {
+        let hex = rgb.trim_start_matches('#');
+        match u32::from_str_radix(hex, 16) {
+        | Ok(rgb) if hex.len() == 6 && hex.bytes().all(|byte| byte.is_ascii_hexdigit()) => Ok(Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)),
+        | _ => Err(format!("Invalid color `{}`: expected `#RRGGBB`", rgb)),
+        }
+    }
+}
+
+/// Terminal color support.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Depth {
+    /// 24-bit color
+    True,
+
+    /// 256-color palette
+    Ansi256,
+
+    /// 16 standard colors
+    Ansi16,
+}
+
+impl Depth {
+    /// Detect color support from the `COLORTERM` and `TERM` environment variables.
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            Depth::True
+        } else if term.contains("256color") {
+            Depth::Ansi256
+        } else {
+            Depth::Ansi16
+        }
+    }
+
+    /// Write the escape code selecting `rgb` as the foreground color.
+    pub fn fg<W: std::io::Write>(self, rgb: Rgb, out: &mut W) -> std::io::Result<()> {
+        match self {
+        | Depth::True => write!(out, "\x1B[38;2;{};{};{}m", rgb.0, rgb.1, rgb.2),
+        | Depth::Ansi256 => write!(out, "\x1B[38;5;{}m", rgb.ansi256()),
+        | Depth::Ansi16 => match rgb.ansi16() {
+            | c if c < 8 => write!(out, "\x1B[{}m", 30 + c),
+            | c => write!(out, "\x1B[{}m", 90 + c - 8),
+        },
+        }
+    }
+
+    /// Write the escape code selecting `rgb` as the background color.
+    pub fn bg<W: std::io::Write>(self, rgb: Rgb, out: &mut W) -> std::io::Result<()> {
+        match self {
+        | Depth::True => write!(out, "\x1B[48;2;{};{};{}m", rgb.0, rgb.1, rgb.2),
+        | Depth::Ansi256 => write!(out, "\x1B[48;5;{}m", rgb.ansi256()),
+        | Depth::Ansi16 => match rgb.ansi16() {
+            | c if c < 8 => write!(out, "\x1B[{}m", 40 + c),
+            | c => write!(out, "\x1B[{}m", 100 + c - 8),
+        },
+        }
+    }
+}
+
+/// Foreground and background colors of the display.
+/// Missing colors use the terminal's defaults.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Palette {
+    /// Color of set pixels
+    pub fg: Option<Rgb>,
+
+    /// Color of unset pixels
+    pub bg: Option<Rgb>,
+}
+
+impl Palette {
+    /// Look up a preset palette by name.
+    pub fn preset(name: &str) -> Result<Self, String> {
+        let (fg, bg) = match name {
+        | "default" => return Ok(Palette::default()),
+        | "green" => (Rgb(0x33, 0xFF, 0x66), Rgb(0x05, 0x14, 0x08)),
+        | "amber" => (Rgb(0xFF, 0xB0, 0x00), Rgb(0x14, 0x0C, 0x00)),
+        | "lcd" => (Rgb(0x0F, 0x38, 0x0F), Rgb(0x9B, 0xBC, 0x0F)),
+        | "inverted" => (Rgb(0x00, 0x00, 0x00), Rgb(0xFF, 0xFF, 0xFF)),
+        | _ => return Err(format!("Unknown palette `{}`: expected `default`, `green`, `amber`, `lcd`, or `inverted`", name)),
+        };
+        Ok(Palette { fg: Some(fg), bg: Some(bg) })
+    }
+}
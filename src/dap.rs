@@ -0,0 +1,791 @@
+//! [Debug Adapter Protocol][0] server for editor integration.
+//!
+//! The server does not own the emulator: the main loop hands it the
+//! current `Chip` every iteration via `Server::poll`, and asks it whether
+//! to stop before each instruction via `Server::stop`.
+//!
+//! [0]: https://microsoft.github.io/debug-adapter-protocol/specification
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::io::{self, BufRead, Write};
+use std::net;
+use std::path;
+use std::sync::mpsc;
+use std::thread;
+
+use serde_json::{json, Value};
+
+use crate::asm;
+use crate::chip;
+use crate::cpu;
+use crate::listing;
+use crate::ram;
+
+/// Thread ID of the single CHIP-8 thread.
+const THREAD: u64 = 1;
+
+/// Variables reference for general purpose, index, and program counter registers.
+const REGISTERS: u64 = 1;
+
+/// Variables reference for sound and delay timers.
+const TIMERS: u64 = 2;
+
+/// Variables reference for stack pointer and stack memory.
+const STACK: u64 = 3;
+
+/// Requests from the client that the main loop must act on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Stop executing instructions
+    Pause,
+
+    /// Resume executing instructions
+    Resume,
+
+    /// Client disconnected
+    Disconnect,
+}
+
+/// Debug Adapter Protocol server for a single client.
+pub struct Server {
+    /// Connection to client
+    stream: net::TcpStream,
+
+    /// Requests parsed by the reader thread
+    rx: mpsc::Receiver<Value>,
+
+    /// Sequence number of the next outgoing message
+    seq: u64,
+
+    /// Optional mapping between source lines and addresses
+    listing: Option<listing::Listing>,
+
+    /// Whether client line numbers are 1-based
+    one: bool,
+
+    /// Whether to stop before the first instruction
+    entry: bool,
+
+    /// Breakpoints set on listing lines
+    lines: HashSet<ram::Addr>,
+
+    /// Breakpoints set on instruction addresses
+    instructions: HashSet<ram::Addr>,
+
+    /// Stop once the stack depth is at most this value
+    until: Option<u8>,
+
+    /// Whether no instruction has executed since resuming
+    fresh: bool,
+}
+
+impl Server {
+    /// Block until a client connects to `port` on localhost.
+    pub fn listen(port: u16, listing: Option<&path::Path>) -> io::Result<Self> {
+        let listing = match listing {
+        | Some(path) => Some(listing::Listing::load(path)?),
+        | None => None,
+        };
+        let (stream, _) = net::TcpListener::bind(("127.0.0.1", port))?.accept()?;
+        Self::new(stream, listing)
+    }
+
+    /// Serve the client connected through `stream`.
+    fn new(stream: net::TcpStream, listing: Option<listing::Listing>) -> io::Result<Self> {
+        let reader = stream.try_clone()?;
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut reader = io::BufReader::new(reader);
+            while let Ok(Some(message)) = receive(&mut reader) {
+                if tx.send(message).is_err() { break }
+            }
+        });
+        Ok(Server {
+            stream,
+            rx,
+            seq: 1,
+            listing,
+            one: true,
+            entry: true,
+            lines: HashSet::new(),
+            instructions: HashSet::new(),
+            until: None,
+            fresh: false,
+        })
+    }
+
+    /// Handle all pending requests without blocking.
+    pub fn poll(&mut self, chip: &chip::Chip) -> io::Result<Option<Command>> {
+        loop {
+            let request = match self.rx.try_recv() {
+            | Ok(request) => request,
+            | Err(mpsc::TryRecvError::Empty) => return Ok(None),
+            | Err(mpsc::TryRecvError::Disconnected) => return Ok(Some(Command::Disconnect)),
+            };
+            if let Some(command) = self.handle(chip, &request)? {
+                return Ok(Some(command));
+            }
+        }
+    }
+
+    /// Check whether to stop before executing the next instruction,
+    /// notifying the client if so.
+    pub fn stop(&mut self, chip: &chip::Chip) -> io::Result<bool> {
+        if std::mem::replace(&mut self.fresh, false) {
+            return Ok(false);
+        }
+        let pc = chip.machine.cpu.pc;
+        let reason = if self.lines.contains(&pc) || self.instructions.contains(&pc) {
+            "breakpoint"
+        } else if self.until.is_some_and(|depth| u8::from(chip.machine.cpu.sp) <= depth) {
+            "step"
+        } else {
+            return Ok(false);
+        };
+        self.until = None;
+        self.stopped(reason)?;
+        Ok(true)
+    }
+
+    /// Notify the client that execution stopped because of `description`.
+    pub fn interrupt(&mut self, description: &str) -> io::Result<()> {
+        self.until = None;
+        self.event("stopped", json!({
+            "reason": "exception",
+            "description": description,
+            "threadId": THREAD,
+            "allThreadsStopped": true,
+        }))
+    }
+
+    /// Dispatch a single request.
+    fn handle(&mut self, chip: &chip::Chip, request: &Value) -> io::Result<Option<Command>> {
+        let args = &request["arguments"];
+        let depth = u8::from(chip.machine.cpu.sp);
+        match request["command"].as_str().unwrap_or_default() {
+        | "initialize" => {
+            self.one = args["linesStartAt1"].as_bool().unwrap_or(true);
+            self.respond(request, json!({
+                "supportsConfigurationDoneRequest": true,
+                "supportsReadMemoryRequest": true,
+                "supportsDisassembleRequest": true,
+                "supportsInstructionBreakpoints": true,
+                "supportsSteppingGranularity": true,
+                "supportsTerminateRequest": true,
+            }))?;
+            self.event("initialized", json!({}))?;
+        }
+        | "launch" | "attach" => {
+            self.entry = args["stopOnEntry"].as_bool().unwrap_or(true);
+            self.respond(request, json!({}))?;
+        }
+        | "configurationDone" => {
+            self.respond(request, json!({}))?;
+            if self.entry {
+                self.stopped("entry")?;
+                return Ok(Some(Command::Pause));
+            }
+            return Ok(Some(Command::Resume));
+        }
+        | "setBreakpoints" => {
+            let body = self.set_breakpoints(args);
+            self.reply(request, body)?;
+        }
+        | "setInstructionBreakpoints" => {
+            let body = self.set_instruction_breakpoints(args);
+            self.reply(request, body)?;
+        }
+        | "setExceptionBreakpoints" => {
+            self.respond(request, json!({ "breakpoints": [] }))?;
+        }
+        | "threads" => {
+            self.respond(request, json!({ "threads": [{ "id": THREAD, "name": "CHIP-8" }] }))?;
+        }
+        | "stackTrace" => {
+            let body = self.stack_trace(chip, args);
+            self.reply(request, body)?;
+        }
+        | "scopes" => {
+            self.respond(request, json!({
+                "scopes": [
+                    { "name": "Registers", "variablesReference": REGISTERS, "expensive": false },
+                    { "name": "Timers", "variablesReference": TIMERS, "expensive": false },
+                    { "name": "Stack", "variablesReference": STACK, "expensive": false },
+                ]
+            }))?;
+        }
+        | "variables" => {
+            let body = int(args, "variablesReference").map(|reference| variables(chip, reference));
+            self.reply(request, body)?;
+        }
+        | "readMemory" => {
+            let body = read_memory(chip, args);
+            self.reply(request, body)?;
+        }
+        | "disassemble" => {
+            let body = self.disassemble(chip, args);
+            self.reply(request, body)?;
+        }
+        | "continue" => {
+            self.resume(None);
+            self.respond(request, json!({ "allThreadsContinued": true }))?;
+            return Ok(Some(Command::Resume));
+        }
+        | "next" => {
+            self.resume(Some(depth));
+            self.respond(request, json!({}))?;
+            return Ok(Some(Command::Resume));
+        }
+        | "stepIn" => {
+            self.resume(Some(u8::MAX));
+            self.respond(request, json!({}))?;
+            return Ok(Some(Command::Resume));
+        }
+        | "stepOut" => {
+            self.resume(depth.checked_sub(1));
+            self.respond(request, json!({}))?;
+            return Ok(Some(Command::Resume));
+        }
+        | "pause" => {
+            self.until = None;
+            self.respond(request, json!({}))?;
+            self.stopped("pause")?;
+            return Ok(Some(Command::Pause));
+        }
+        | "disconnect" | "terminate" => {
+            self.respond(request, json!({}))?;
+            return Ok(Some(Command::Disconnect));
+        }
+        | command => {
+            self.fail(request, &format!("Unsupported request: {}", command))?;
+        }
+        }
+        Ok(None)
+    }
+
+    /// Resume execution, stopping once the stack depth is at most `until`.
+    fn resume(&mut self, until: Option<u8>) {
+        self.until = until;
+        self.fresh = true;
+    }
+
+    fn set_breakpoints(&mut self, args: &Value) -> Result<Value, String> {
+        let source = args["source"]["path"].as_str().map(path::Path::new);
+        let listing = self.listing
+            .as_ref()
+            .filter(|listing| source.is_some_and(|source| same(source, listing.path())));
+        let mut lines = HashSet::new();
+        let breakpoints = args["breakpoints"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|breakpoint| {
+                let line = int::<u32>(breakpoint, "line")?;
+                let line = if self.one { line } else { line.checked_add(1).ok_or_else(|| range("line"))? };
+                Ok(match listing.and_then(|listing| listing.addr(line)) {
+                | Some(addr) => {
+                    lines.insert(addr);
+                    json!({
+                        "verified": true,
+                        "line": self.line(line),
+                        "instructionReference": addr.to_string(),
+                    })
+                }
+                | None => json!({
+                    "verified": false,
+                    "message": "No instruction assembled from this line",
+                }),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        self.lines = lines;
+        Ok(json!({ "breakpoints": breakpoints }))
+    }
+
+    fn set_instruction_breakpoints(&mut self, args: &Value) -> Result<Value, String> {
+        let mut instructions = HashSet::new();
+        let breakpoints = args["breakpoints"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|breakpoint| {
+                let offset = int(breakpoint, "offset")?;
+                Ok(match reference(&breakpoint["instructionReference"], offset) {
+                | Some(addr) => {
+                    instructions.insert(addr);
+                    json!({ "verified": true, "instructionReference": addr.to_string() })
+                }
+                | None => json!({ "verified": false, "message": "Invalid address" }),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        self.instructions = instructions;
+        Ok(json!({ "breakpoints": breakpoints }))
+    }
+
+    fn stack_trace(&self, chip: &chip::Chip, args: &Value) -> Result<Value, String> {
+        let calls = chip.machine.stack
+            .frames(chip.machine.cpu.sp)
+            .iter()
+            .rev()
+            .map(|ret| ret.offset(-2).unwrap_or(*ret));
+        let frames = std::iter::once(chip.machine.cpu.pc)
+            .chain(calls)
+            .enumerate()
+            .map(|(id, addr)| self.frame(chip, id, addr))
+            .collect::<Vec<_>>();
+        let total = frames.len();
+        let start = int(args, "startFrame")?;
+        let levels = match int(args, "levels")? {
+        | 0 => total,
+        | levels => levels,
+        };
+        let frames = frames.into_iter().skip(start).take(levels).collect::<Vec<_>>();
+        Ok(json!({ "stackFrames": frames, "totalFrames": total }))
+    }
+
+    fn frame(&self, chip: &chip::Chip, id: usize, addr: ram::Addr) -> Value {
+        let mut frame = json!({
+            "id": id,
+            "name": format!("{}: {}", addr, disassemble(chip, addr)),
+            "line": 0,
+            "column": 0,
+            "instructionPointerReference": addr.to_string(),
+        });
+        if let Some(line) = self.listing.as_ref().and_then(|listing| listing.line(addr)) {
+            frame["line"] = json!(self.line(line));
+            frame["column"] = json!(if self.one { 1 } else { 0 });
+            frame["source"] = self.source();
+        }
+        frame
+    }
+
+    fn disassemble(&self, chip: &chip::Chip, args: &Value) -> Result<Value, String> {
+        let offset = int::<i32>(args, "offset")? as i64 + int::<i32>(args, "instructionOffset")? as i64 * 2;
+        let count = int::<u32>(args, "instructionCount")?.min(0x1000 / 2) as i64;
+        let base = args["memoryReference"]
+            .as_str()
+            .and_then(parse)
+            .map(|addr| addr as i64 + offset)
+            .unwrap_or(offset);
+        let instructions = (0..count)
+            .map(|index| base + index * 2)
+            .map(|addr| match offset_addr(addr).filter(|addr| addr.offset(1).is_some()) {
+            | None => json!({
+                "address": format!("{:#05X}", addr.max(0)),
+                "instruction": "??",
+                "presentationHint": "invalid",
+            }),
+            | Some(addr) => {
+                let hi = chip.machine.ram[addr];
+                let lo = chip.machine.ram[addr + 1];
+                let mut instruction = json!({
+                    "address": addr.to_string(),
+                    "instructionBytes": format!("{:02X}{:02X}", hi, lo),
+                    "instruction": disassemble(chip, addr),
+                });
+                if let Some(line) = self.listing.as_ref().and_then(|listing| listing.line(addr)) {
+                    instruction["line"] = json!(self.line(line));
+                    instruction["location"] = self.source();
+                }
+                instruction
+            }
+            })
+            .collect::<Vec<_>>();
+        Ok(json!({ "instructions": instructions }))
+    }
+
+    /// Convert a 1-based listing line into a client line.
+    fn line(&self, line: u32) -> u32 {
+        if self.one { line } else { line - 1 }
+    }
+
+    fn source(&self) -> Value {
+        match &self.listing {
+        | None => Value::Null,
+        | Some(listing) => json!({
+            "name": listing.path().file_name().map(|name| name.to_string_lossy()),
+            "path": listing.path().to_string_lossy(),
+        }),
+        }
+    }
+
+    fn stopped(&mut self, reason: &str) -> io::Result<()> {
+        self.event("stopped", json!({
+            "reason": reason,
+            "threadId": THREAD,
+            "allThreadsStopped": true,
+        }))
+    }
+
+    fn event(&mut self, event: &str, body: Value) -> io::Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    fn respond(&mut self, request: &Value, body: Value) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": true,
+            "body": body,
+        }))
+    }
+
+    fn fail(&mut self, request: &Value, message: &str) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": false,
+            "message": message,
+        }))
+    }
+
+    /// Respond with `body`, or fail with its error message.
+    fn reply(&mut self, request: &Value, body: Result<Value, String>) -> io::Result<()> {
+        match body {
+        | Ok(body) => self.respond(request, body),
+        | Err(message) => self.fail(request, &message),
+        }
+    }
+
+    fn send(&mut self, mut message: Value) -> io::Result<()> {
+        message["seq"] = json!(self.seq);
+        self.seq += 1;
+        let body = message.to_string();
+        write!(self.stream, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+        self.stream.flush()
+    }
+}
+
+fn variables(chip: &chip::Chip, reference: u64) -> Value {
+    let variable = |name: String, value: String| json!({
+        "name": name,
+        "value": value,
+        "variablesReference": 0,
+    });
+    let pointer = |name: &str, addr: ram::Addr| json!({
+        "name": name,
+        "value": addr.to_string(),
+        "variablesReference": 0,
+        "memoryReference": addr.to_string(),
+    });
+    let variables = match reference {
+    | REGISTERS => (0x0..=0xF)
+        .map(|offset| cpu::V0 + offset)
+        .map(|x| variable(x.to_string(), format!("{:#04X}", chip.machine.cpu[x])))
+        .chain(std::iter::once(pointer("I", chip.machine.cpu.idx)))
+        .chain(std::iter::once(pointer("PC", chip.machine.cpu.pc)))
+        .collect(),
+    | TIMERS => vec![
+        variable("DT".to_string(), format!("{:#04X}", chip.machine.cpu.dt)),
+        variable("ST".to_string(), format!("{:#04X}", chip.machine.cpu.st)),
+    ],
+    | STACK => std::iter::once(variable("SP".to_string(), chip.machine.cpu.sp.to_string()))
+        .chain(chip.machine.stack
+            .frames(chip.machine.cpu.sp)
+            .iter()
+            .enumerate()
+            .map(|(depth, addr)| pointer(&format!("[{:X}]", depth), *addr)))
+        .collect(),
+    | _ => Vec::new(),
+    };
+    json!({ "variables": variables })
+}
+
+fn read_memory(chip: &chip::Chip, args: &Value) -> Result<Value, String> {
+    let offset = int::<i32>(args, "offset")?;
+    let count = int::<u32>(args, "count")?;
+    let start = match reference(&args["memoryReference"], offset) {
+    | Some(start) => start,
+    | None => return Ok(json!({ "address": format!("{:#05X}", offset.max(0)), "unreadableBytes": count })),
+    };
+    let data = (0..count.min(0x1000))
+        .map_while(|offset| start.offset(offset as i32))
+        .map(|addr| chip.machine.ram[addr])
+        .collect::<Vec<_>>();
+    Ok(json!({
+        "address": start.to_string(),
+        "data": base64(&data),
+        "unreadableBytes": count - data.len() as u32,
+    }))
+}
+
+/// Disassemble the instruction at `addr`.
+fn disassemble(chip: &chip::Chip, addr: ram::Addr) -> String {
+    match addr.offset(1).and_then(|next| asm::Asm::parse(chip.machine.ram[addr], chip.machine.ram[next])) {
+    | Some(op) => op.to_string(),
+    | None => String::from("???"),
+    }
+}
+
+/// Resolve a memory or instruction reference plus byte offset into an address.
+fn reference(reference: &Value, offset: i32) -> Option<ram::Addr> {
+    let base = reference.as_str().and_then(parse)?;
+    offset_addr(base as i64 + offset as i64)
+}
+
+/// Address `addr` bytes from 0x000, if it is in memory.
+fn offset_addr(addr: i64) -> Option<ram::Addr> {
+    i32::try_from(addr).ok().and_then(|addr| ram::Addr::from(0).offset(addr))
+}
+
+/// Integer argument `name` of `args`, or zero if absent.
+fn int<T: TryFrom<i64> + Default>(args: &Value, name: &str) -> Result<T, String> {
+    match &args[name] {
+    | Value::Null => Ok(T::default()),
+    | value => value.as_i64().and_then(|value| T::try_from(value).ok()).ok_or_else(|| range(name)),
+    }
+}
+
+/// Error message for integer argument `name` out of range.
+fn range(name: &str) -> String {
+    format!("Argument `{}` is out of range", name)
+}
+
+/// Parse a hexadecimal reference such as `0x200`.
+fn parse(reference: &str) -> Option<u16> {
+    u16::from_str_radix(reference.trim_start_matches("0x").trim_start_matches("0X"), 16).ok()
+}
+
+/// Whether two paths refer to the same file.
+fn same(lhs: &path::Path, rhs: &path::Path) -> bool {
+    match (lhs.canonicalize(), rhs.canonicalize()) {
+    | (Ok(lhs), Ok(rhs)) => lhs == rhs,
+    | _ => lhs == rhs,
+    }
+}
+
+/// Read a single `Content-Length` framed message.
+fn receive<R: BufRead>(reader: &mut R) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 { return Ok(None) }
+        let header = header.trim();
+        if header.is_empty() { break }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length"))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body).map(Some).map_err(io::Error::from)
+}
+
+/// Standard base64 encoding with padding.
+fn base64(data: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| bits | (*byte as u32) << (16 - i * 8));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(TABLE[(bits >> (18 - i * 6)) as usize & 0x3F] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::net;
+
+    use serde_json::{json, Value};
+
+    use super::{base64, receive, Command, Server};
+    use crate::chip::Chip;
+    use crate::listing::Listing;
+
+    /// Call `CLS` at 0x206 from 0x200, then loop at 0x202.
+    const ROM: [u8; 10] = [0x22, 0x06, 0x12, 0x02, 0x00, 0x00, 0x00, 0xE0, 0x00, 0xEE];
+
+    /// Server connected to a client over localhost, and the client's end.
+    fn connect(listing: Option<Listing>) -> (Server, io::BufReader<net::TcpStream>) {
+        let listener = net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let client = net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        (Server::new(stream, listing).unwrap(), io::BufReader::new(client))
+    }
+
+    /// Handle `command` with `arguments`, and return the resulting command and
+    /// response, skipping any events sent before it.
+    fn request(
+        (server, client): &mut (Server, io::BufReader<net::TcpStream>),
+        chip: &Chip,
+        command: &str,
+        arguments: Value,
+    ) -> (Option<Command>, Value) {
+        let command = server.handle(chip, &json!({ "seq": 1, "command": command, "arguments": arguments })).unwrap();
+        let response = std::iter::repeat_with(|| receive(client).unwrap().unwrap())
+            .find(|message| message["type"] == "response")
+            .unwrap();
+        (command, response)
+    }
+
+    /// Step `chip` like the main loop until `server` stops it, returning the
+    /// instructions executed, or `None` if it never stops.
+    fn run(server: &mut Server, chip: &mut Chip) -> Option<usize> {
+        for count in 0..100 {
+            if server.stop(chip).unwrap() { return Some(count) }
+            chip.step().unwrap();
+        }
+        None
+    }
+
+    #[test]
+    fn breakpoints() {
+        let path = std::env::temp_dir().join(format!("chip-{}-dap.lst", std::process::id()));
+        std::fs::write(&path, "; Call CLS\n0x200: 2206 CALL 0x206\n0x202: 1202 JP 0x202\n0x206: 00E0 CLS\n").unwrap();
+        let mut chip = Chip::new(ROM.iter().cloned());
+        let mut dap = connect(Some(Listing::load(&path).unwrap()));
+        let source = json!({ "path": path });
+
+        let (_, response) = request(&mut dap, &chip, "setBreakpoints", json!({
+            "source": source,
+            "breakpoints": [{ "line": 1 }, { "line": 4 }],
+        }));
+        let breakpoints = &response["body"]["breakpoints"];
+        assert_eq!(breakpoints[0]["verified"], false);
+        assert_eq!((&breakpoints[1]["verified"], &breakpoints[1]["line"]), (&json!(true), &json!(4)));
+        assert_eq!(breakpoints[1]["instructionReference"], "0x206");
+        assert_eq!(run(&mut dap.0, &mut chip), Some(1));
+        assert_eq!(chip.pc(), 0x206.into());
+
+        // Zero-based lines, with the same breakpoint
+        request(&mut dap, &chip, "initialize", json!({ "linesStartAt1": false }));
+        let (_, response) = request(&mut dap, &chip, "setBreakpoints", json!({ "source": source, "breakpoints": [{ "line": 3 }] }));
+        assert_eq!(response["body"]["breakpoints"][0]["line"], 3);
+
+        let (_, response) = request(&mut dap, &chip, "setBreakpoints", json!({ "source": source, "breakpoints": [{ "line": u32::MAX }] }));
+        assert_eq!((&response["success"], &response["message"]), (&json!(false), &json!("Argument `line` is out of range")));
+        let (_, response) = request(&mut dap, &chip, "setBreakpoints", json!({ "source": source, "breakpoints": [{ "line": -1 }] }));
+        assert_eq!(response["success"], false);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn instruction_breakpoints() {
+        let mut chip = Chip::new(ROM.iter().cloned());
+        let mut dap = connect(None);
+        let (_, response) = request(&mut dap, &chip, "setInstructionBreakpoints", json!({
+            "breakpoints": [
+                { "instructionReference": "0x200", "offset": 8 },
+                { "instructionReference": "0x200", "offset": 0xE00 },
+            ],
+        }));
+        let breakpoints = &response["body"]["breakpoints"];
+        assert_eq!(breakpoints[0]["instructionReference"], "0x208");
+        assert_eq!(breakpoints[1]["verified"], false);
+        assert_eq!(run(&mut dap.0, &mut chip), Some(2));
+
+        // A failed request leaves the breakpoints as they were
+        let (_, response) = request(&mut dap, &chip, "setInstructionBreakpoints", json!({
+            "breakpoints": [{ "instructionReference": "0x200", "offset": 1u64 << 32 }],
+        }));
+        assert_eq!(response["success"], false);
+        assert!(dap.0.instructions.contains(&0x208.into()));
+    }
+
+    #[test]
+    fn stepping() {
+        let mut chip = Chip::new(ROM.iter().cloned());
+        let mut dap = connect(None);
+
+        // Over the call, to the instruction after it
+        assert_eq!(request(&mut dap, &chip, "next", json!({})).0, Some(Command::Resume));
+        assert_eq!(run(&mut dap.0, &mut chip), Some(3));
+        assert_eq!(chip.pc(), 0x202.into());
+
+        // Into the call, then back out of it
+        let mut chip = Chip::new(ROM.iter().cloned());
+        assert_eq!(request(&mut dap, &chip, "stepIn", json!({})).0, Some(Command::Resume));
+        assert_eq!(run(&mut dap.0, &mut chip), Some(1));
+        assert_eq!(chip.pc(), 0x206.into());
+        assert_eq!(request(&mut dap, &chip, "stepOut", json!({})).0, Some(Command::Resume));
+        assert_eq!(run(&mut dap.0, &mut chip), Some(2));
+        assert_eq!(chip.pc(), 0x202.into());
+
+        // Stepping out of the outermost frame runs freely
+        request(&mut dap, &chip, "stepOut", json!({}));
+        assert_eq!(run(&mut dap.0, &mut chip), None);
+    }
+
+    #[test]
+    fn disassemble() {
+        let chip = Chip::new(ROM.iter().cloned());
+        let mut dap = connect(None);
+        let (_, response) = request(&mut dap, &chip, "disassemble", json!({
+            "memoryReference": "0xFFC",
+            "instructionCount": u32::MAX,
+        }));
+        let instructions = response["body"]["instructions"].as_array().unwrap();
+        assert_eq!(instructions.len(), 0x800);
+        assert_eq!(instructions[1]["address"], "0xFFE");
+        assert_eq!((&instructions[2]["address"], &instructions[2]["instruction"]), (&json!("0x1000"), &json!("??")));
+
+        let (_, response) = request(&mut dap, &chip, "disassemble", json!({ "memoryReference": "0x200", "offset": 1u64 << 31 }));
+        assert_eq!(response["message"], "Argument `offset` is out of range");
+    }
+
+    #[test]
+    fn variables() {
+        let mut chip = Chip::new(ROM.iter().cloned());
+        chip.step().unwrap();
+        let mut dap = connect(None);
+        let mut variables = |reference: Value| request(&mut dap, &chip, "variables", json!({ "variablesReference": reference })).1;
+
+        let registers = variables(json!(1));
+        let registers = registers["body"]["variables"].as_array().unwrap();
+        assert_eq!(registers.len(), 18);
+        assert_eq!((&registers[0]["name"], &registers[0]["value"]), (&json!("V0"), &json!("0x00")));
+        assert_eq!((&registers[17]["name"], &registers[17]["memoryReference"]), (&json!("PC"), &json!("0x206")));
+
+        let timers = variables(json!(2));
+        assert_eq!(timers["body"]["variables"][1]["name"], "ST");
+
+        let stack = variables(json!(3));
+        let stack = stack["body"]["variables"].as_array().unwrap();
+        assert_eq!(stack[0]["value"], "0x1");
+        assert_eq!((&stack[1]["name"], &stack[1]["value"]), (&json!("[0]"), &json!("0x202")));
+
+        assert_eq!(variables(json!(4))["body"]["variables"], json!([]));
+        assert_eq!(variables(json!(-1))["success"], false);
+    }
+
+    #[test]
+    fn framing() {
+        let mut reader = io::Cursor::new(&b"Content-Length: 13\r\n\r\n{\"seq\": 1}   Content-Type: json\r\nContent-Length:7\r\n\r\n[1,2,3]"[..]);
+        assert_eq!(receive(&mut reader).unwrap(), Some(json!({ "seq": 1 })));
+        assert_eq!(receive(&mut reader).unwrap(), Some(json!([1, 2, 3])));
+        assert_eq!(receive(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn framing_errors() {
+        let missing = receive(&mut io::Cursor::new(&b"Content-Type: json\r\n\r\n{}"[..])).unwrap_err();
+        assert_eq!(missing.kind(), io::ErrorKind::InvalidData);
+        let short = receive(&mut io::Cursor::new(&b"Content-Length: 10\r\n\r\n{}"[..])).unwrap_err();
+        assert_eq!(short.kind(), io::ErrorKind::UnexpectedEof);
+        assert!(receive(&mut io::Cursor::new(&b"Content-Length: 2\r\n\r\n{]"[..])).is_err());
+    }
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0x00, 0xF0, 0xFF]), "APD/");
+    }
+}
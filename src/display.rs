@@ -1,64 +1,248 @@
-use std::collections::HashSet;
+use crate::color;
 
-/// Width
-pub const W: u8 = 64;
+pub use chip_core::display::{Display, H, MSB, W};
 
-/// Height
-pub const H: u8 = 32;
+/// Longest run of unchanged columns redrawn instead of moving the cursor past them
+const GAP: u16 = 2;
 
-/// Leftmost bit for masking pixels
-const MSB: u64 = 0x8000_0000_0000_0000;
+/// Rendered terminal cell: glyph, foreground, and background color.
+type Cell = (char, Option<color::Rgb>, Option<color::Rgb>);
 
+/// Terminal rendering of pixels.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Render {
+    /// One `█` per pixel
+    #[default]
+    Block,
+
+    /// One `▀`, `▄`, or `█` per 1x2 pixels
+    Half,
+
+    /// One braille pattern per 2x4 pixels
+    Braille,
+
+    /// Two `█` per pixel, for roughly square pixels
+    Square,
+}
+
+impl Render {
+    /// Pixels covered by each terminal cell.
+    fn pixels(self) -> (u8, u8) {
+        match self {
+        | Render::Block | Render::Square => (1, 1),
+        | Render::Half => (1, 2),
+        | Render::Braille => (2, 4),
+        }
+    }
+
+    /// Terminal columns taken by each cell.
+    fn width(self) -> u16 {
+        match self {
+        | Render::Square => 2,
+        | _ => 1,
+        }
+    }
+}
+
+impl std::str::FromStr for Render {
+    type Err = String;
+    fn from_str(render: &str) -> Result<Self, Self::Err> {
+        match render {
+        | "block" => Ok(Render::Block),
+        | "half" => Ok(Render::Half),
+        | "braille" => Ok(Render::Braille),
+        | "square" => Ok(Render::Square),
+        | _ => Err(format!("Unknown render mode `{}`: expected `block`, `half`, `braille`, or `square`", render)),
+        }
+    }
+}
+
+/// Terminal rendering of a `Display`.
 #[derive(Clone, Debug)]
-pub struct Display {
-    /// Pixel grid
-    grid: [u64; H as usize],
+pub struct Screen {
+    /// Cells as last drawn to the terminal, row by row, or `None` if unknown
+    presented: Vec<Option<Cell>>,
 
-    /// Dirty pixels
-    dirt: HashSet<(u8, u8)>,
+    /// Terminal rendering of pixels
+    render: Render,
+
+    /// Frames left before each recently unset pixel fades out
+    fade: [[u8; W as usize]; H as usize],
+
+    /// Frames that unset pixels take to fade out
+    persist: u8,
+
+    /// Pixel colors
+    palette: color::Palette,
+
+    /// Terminal color support
+    depth: color::Depth,
 }
 
-impl Display {
-    /// Blank out the display.
-    pub fn clear(&mut self) {
-        self.dirt.clear();
-        for (y, row) in self.grid.iter_mut().enumerate() {
-            let mut col = MSB;
-            for x in 0..W {
-                if *row & col > 0 { self.dirt.insert((x, y as u8)); }
-                col >>= 1;
+impl Screen {
+    /// Start fading out pixels set in `before` but not `after`,
+    /// and stop fading pixels set again.
+    pub fn fade(&mut self, before: &[u64; H as usize], after: &[u64; H as usize]) {
+        if self.persist == 0 { return }
+        for (y, (before, after)) in before.iter().zip(after).enumerate() {
+            let changed = before ^ after;
+            if changed == 0 { continue }
+            for x in (0..W).filter(|&x| changed & (MSB >> x) > 0) {
+                self.fade[y][x as usize] = if after & (MSB >> x) > 0 { 0 } else { self.persist };
             }
-            *row = 0;
         }
     }
 
-    /// Toggle the pixel at coordinates `(x, y)`.
-    pub fn toggle(&mut self, x: u8, y: u8) -> u8 {
-        let (x, y) = (x % W, y % H);
-        let bit = MSB >> x;
-        let hit = self.grid[y as usize] & bit > 0;
-        self.grid[y as usize] ^= bit;
-        self.dirt.insert((x, y));
-        hit as u8
+    /// Brightness of the pixel at coordinates `(x, y)`, from 0 (unset) to 255 (set).
+    /// Recently unset pixels are dimmer the longer ago they were unset.
+    fn level(&self, display: &Display, x: u8, y: u8) -> u8 {
+        if x >= W || y >= H { return 0 }
+        if display.get(x, y) { return 255 }
+        (self.fade[y as usize][x as usize] as u16 * 255 / (self.persist as u16 + 1)) as u8
+    }
+
+    /// Advance one 60Hz frame, fading out recently unset pixels.
+    pub fn frame(&mut self) {
+        if self.persist == 0 { return }
+        for fade in self.fade.iter_mut().flatten() {
+            *fade = fade.saturating_sub(1);
+        }
+    }
+
+    /// Keep unset pixels visible for `frames` frames with dimmer shades,
+    /// reducing flicker from sprites that are erased and redrawn.
+    pub fn set_persist(&mut self, frames: u8) {
+        self.persist = frames;
+        self.fade = [[0; W as usize]; H as usize];
+    }
+
+    /// Change how pixels are rendered, redrawing the whole display.
+    pub fn set_render(&mut self, render: Render) {
+        self.render = render;
+        self.invalidate();
     }
 
-    /// Draw the display at offset `(dx, dy)`.
-    pub fn draw<W: std::io::Write>(&mut self, dx: u16, dy: u16, out: &mut W) -> std::io::Result<()> {
-        for (x, y) in self.dirt.drain() {
-            let set = (self.grid[y as usize] & (MSB >> x)) > 0;
-            let bit = if set { '█' } else { ' ' };
-            let go = termion::cursor::Goto(x as u16 + dx, y as u16 + dy);
-            write!(out, "{}{}", go, bit)?;
+    /// Forget what was last drawn, so the next draw redraws the whole display.
+    pub fn invalidate(&mut self) {
+        self.presented.clear();
+    }
+
+    /// Change the pixel colors, redrawing the whole display.
+    pub fn set_palette(&mut self, palette: color::Palette, depth: color::Depth) {
+        self.palette = palette;
+        self.depth = depth;
+        self.invalidate();
+    }
+
+    /// Size of the rendered display in terminal columns and rows.
+    pub fn size(&self) -> (u16, u16) {
+        let (pw, ph) = self.render.pixels();
+        let cols = (W / pw) as u16 * self.render.width();
+        let rows = (H / ph) as u16;
+        (cols, rows)
+    }
+
+    /// Render the terminal cell of `display` containing pixels starting at `(x, y)`,
+    /// with its foreground and background colors.
+    ///
+    /// With both palette colors set, fading pixels blend between them.
+    /// Otherwise they are shaded with `▓▒░` or held until they fade out.
+    fn cell(&self, display: &Display, x: u8, y: u8) -> Cell {
+        let (fg, bg) = (self.palette.fg, self.palette.bg);
+        let blend = match (fg, bg) {
+        | (Some(fg), Some(bg)) => Some(move |level| bg.blend(fg, level)),
+        | _ => None,
+        };
+        match self.render {
+        | Render::Block | Render::Square => match (self.level(display, x, y), blend) {
+            | (level, Some(blend)) => ('█', Some(blend(level)), bg),
+            | (0, None) => (' ', fg, bg),
+            | (255, None) => ('█', fg, bg),
+            | (level, None) if level >= 170 => ('▓', fg, bg),
+            | (level, None) if level >= 85 => ('▒', fg, bg),
+            | (_, None) => ('░', fg, bg),
+        },
+        | Render::Half => match ((self.level(display, x, y), self.level(display, x, y + 1)), blend) {
+            | ((top, bottom), Some(blend)) => ('▀', Some(blend(top)), Some(blend(bottom))),
+            | ((0, 0), None) => (' ', fg, bg),
+            | ((_, 0), None) => ('▀', fg, bg),
+            | ((0, _), None) => ('▄', fg, bg),
+            | (_, None) => ('█', fg, bg),
+        },
+        | Render::Braille => {
+            const DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+            let levels = (0..2)
+                .flat_map(|dx| (0..4).map(move |dy| (dx, dy)))
+                .map(|(dx, dy)| (dx, dy, self.level(display, x + dx, y + dy)))
+                .filter(|&(_, _, level)| level > 0)
+                .collect::<Vec<_>>();
+            let dots = levels
+                .iter()
+                .fold(0, |dots, &(dx, dy, _)| dots | DOTS[dx as usize][dy as usize]);
+            let level = levels.iter().map(|&(_, _, level)| level).max().unwrap_or(0);
+            let glyph = if dots == 0 { ' ' } else { std::char::from_u32(0x2800 + dots).unwrap_or(' ') };
+            (glyph, blend.map(|blend| blend(level)).or(fg), bg)
+        }
+        }
+    }
+
+    /// Draw `display` at offset `(dx, dy)`, writing only the cells that changed
+    /// since the last draw.
+    ///
+    /// Changed cells are written in runs along each row, moving the cursor
+    /// only to skip more than `GAP` unchanged columns.
+    pub fn draw<W: std::io::Write>(&mut self, display: &Display, dx: u16, dy: u16, out: &mut W) -> std::io::Result<()> {
+        let (pw, ph) = self.render.pixels();
+        let width = self.render.width();
+        let (cols, rows) = ((W / pw) as usize, (H / ph) as usize);
+        if self.presented.len() != cols * rows {
+            self.presented = vec![None; cols * rows];
+        }
+
+        let mut color = (None, None);
+        for cy in 0..rows {
+            let row = (0..cols)
+                .map(|cx| self.cell(display, cx as u8 * pw, cy as u8 * ph))
+                .collect::<Vec<_>>();
+            let presented = &mut self.presented[cy * cols..(cy + 1) * cols];
+            let mut cursor = None;
+            for cx in 0..cols {
+                if presented[cx] == Some(row[cx]) { continue }
+                let start = match cursor {
+                | Some(next) if (cx - next) as u16 * width <= GAP => next,
+                | _ => {
+                    let go = termion::cursor::Goto(cx as u16 * width + dx + 1, cy as u16 + dy + 1);
+                    write!(out, "{}", go)?;
+                    cx
+                }
+                };
+                for (glyph, fg, bg) in &row[start..=cx] {
+                    if let Some(fg) = fg.filter(|&fg| color.0 != Some(fg)) { self.depth.fg(fg, out)?; }
+                    if let Some(bg) = bg.filter(|&bg| color.1 != Some(bg)) { self.depth.bg(bg, out)?; }
+                    color = (*fg, *bg);
+                    for _ in 0..width { write!(out, "{}", glyph)?; }
+                }
+                presented[cx] = Some(row[cx]);
+                cursor = Some(cx + 1);
+            }
+        }
+
+        if color != (None, None) {
+            write!(out, "{}{}", termion::color::Fg(termion::color::Reset), termion::color::Bg(termion::color::Reset))?;
         }
         Ok(())
     }
 }
 
-impl Default for Display {
+impl Default for Screen {
     fn default() -> Self {
-        Display {
-            grid: [0; H as usize],
-            dirt: (0..H).flat_map(|y| (0..W).map(move |x| (x, y))).collect(),
+        Screen {
+            presented: Vec::new(),
+            render: Render::default(),
+            fade: [[0; W as usize]; H as usize],
+            persist: 0,
+            palette: color::Palette::default(),
+            depth: color::Depth::Ansi16,
         }
     }
 }
@@ -0,0 +1,113 @@
+//! Runs arbitrary bytes as emulator input, for fuzzing.
+//!
+//! Shared by the cargo-fuzz targets in `fuzz/` and the randomized runner in
+//! `tests/fuzz.rs`. Any panic while running an `Input` is a bug: every error
+//! a ROM can cause should surface as a `Fault` instead.
+
+use crate::chip;
+use crate::fault;
+use crate::profile;
+use crate::quirks;
+use crate::watch;
+
+/// Most ROM bytes that fit in memory after `ram::PROGRAM_OFFSET`.
+const ROM_SIZE: usize = 0x1000 - 0x200;
+
+/// Most key events per input.
+const KEYS: usize = 64;
+
+/// Instructions per 60Hz frame.
+const STEPS: u32 = 10;
+
+/// Keypad press or release before a frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Key {
+    /// Frame to press or release the key before
+    pub frame: u8,
+
+    /// CHIP-8 key
+    pub key: u8,
+
+    /// Whether the key is pressed rather than released
+    pub press: bool,
+}
+
+/// Emulator options, keypad sequence, and ROM decoded from fuzzer bytes.
+#[derive(Clone, Debug)]
+pub struct Input {
+    /// Emulator configuration
+    pub options: chip::Options,
+
+    /// Keypad sequence, in no particular order
+    pub keys: Vec<Key>,
+
+    /// Program loaded at `ram::PROGRAM_OFFSET`
+    pub rom: Vec<u8>,
+}
+
+impl Input {
+    /// Decode `data`, laid out as:
+    ///
+    /// - One byte of quirk flags in the low five bits, and fault policy in the next two
+    /// - One byte counting key events, modulo 64
+    /// - Two bytes per key event: the frame, then the key in the low nibble and
+    ///   whether it is pressed in bit 4
+    /// - The ROM, truncated to fit in memory
+    ///
+    /// Missing bytes are treated as zero, so every input decodes.
+    pub fn decode(data: &[u8]) -> Self {
+        let byte = |index: usize| data.get(index).cloned().unwrap_or(0);
+
+        let flags = byte(0);
+        let bit = |index: u8| flags & (1 << index) > 0;
+        let quirks = quirks::Quirks {
+            vf_reset: bit(0),
+            memory: bit(1),
+            shift: bit(2),
+            jump: bit(3),
+            clip: bit(4),
+        };
+        let fault = match (flags >> 5) & 0b11 {
+        | 1 => fault::Policy::Wrap,
+        | 2 => fault::Policy::Error,
+        | _ => fault::Policy::Trap,
+        };
+        // Fixed seed, so crashes that depend on `RND` reproduce
+        let options = chip::Options { fault, quirks, seed: Some(0), ..chip::Options::default() };
+
+        let count = byte(1) as usize % KEYS;
+        let keys = (0..count)
+            .map(|index| {
+                let event = byte(3 + 2 * index);
+                Key { frame: byte(2 + 2 * index), key: event & 0xF, press: event & 0x10 > 0 }
+            })
+            .collect();
+
+        let start = (2 + 2 * count).min(data.len());
+        let rom = data[start..].iter().cloned().take(ROM_SIZE).collect();
+
+        Input { options, keys, rom }
+    }
+
+    /// Run for up to `frames` 60Hz frames, with profiling and every warning enabled,
+    /// and return the fault that stopped execution, if any.
+    pub fn run(&self, frames: u32) -> Option<fault::Fault> {
+        let mut chip = chip::Chip::with_options(self.rom.iter().cloned(), self.options);
+        let mut profile = profile::Profile::default();
+        let mut watch = watch::Watch::default().smc().strict(self.options.font, self.options.load);
+        for frame in 0..frames {
+            for key in self.keys.iter().filter(|key| key.frame as u32 == frame) {
+                if key.press { chip.press(key.key) } else { chip.release(key.key) }
+            }
+            for _ in 0..STEPS {
+                if let Err(fault) = chip.step_traced(&mut (&mut profile, &mut watch)) {
+                    return Some(fault);
+                }
+                watch.drain().for_each(drop);
+            }
+            chip.tick();
+        }
+        profile.report(&chip, 10, &mut std::io::sink()).ok();
+        None
+    }
+}
@@ -0,0 +1,123 @@
+/// Frames that a key stays highlighted after being pressed or polled.
+const HIGHLIGHT: u8 = 10;
+
+/// Keyboard keys taken by the emulator's own hotkeys, which can't be bound.
+const HOTKEYS: [char; 6] = [' ', '+', '-', 'n', 'p', 'r'];
+
+/// Keyboard keys bound to CHIP-8 keys 0 through F.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Keymap([char; 16]);
+
+impl Keymap {
+    /// CHIP-8 key bound to keyboard key `c`.
+    pub fn key(&self, c: char) -> Option<u8> {
+        self.0.iter().position(|&bound| bound == c).map(|key| key as u8)
+    }
+
+    /// Keyboard key bound to CHIP-8 key `key`.
+    pub fn char(&self, key: u8) -> char {
+        self.0[key as usize & 0xF]
+    }
+}
+
+impl Default for Keymap {
+    /// Left side of a QWERTY keyboard, laid out like the COSMAC VIP keypad,
+    /// with `t` for D since `r` resets.
+    fn default() -> Self {
+        Keymap(['x', '1', '2', '3', 'q', 'w', 'e', 'a', 's', 'd', 'z', 'c', '4', 't', 'f', 'v'])
+    }
+}
+
+impl std::str::FromStr for Keymap {
+    type Err = String;
+    fn from_str(keymap: &str) -> Result<Self, Self::Err> {
+        let chars = keymap.chars().collect::<Vec<_>>();
+        if chars.len() != 16 {
+            return Err(format!("Invalid keymap `{}`: expected 16 keys, bound to 0 through F", keymap));
+        }
+        let mut keys = ['\0'; 16];
+        for (key, &c) in chars.iter().enumerate() {
+            if HOTKEYS.contains(&c) {
+                return Err(format!("Invalid keymap `{}`: `{}` is a hotkey", keymap, c));
+            }
+            if keys.contains(&c) {
+                return Err(format!("Invalid keymap `{}`: `{}` is bound twice", keymap, c));
+            }
+            keys[key] = c;
+        }
+        Ok(Keymap(keys))
+    }
+}
+
+/// Recent keypad activity, for highlighting in the keypad panel.
+#[derive(Clone, Debug, Default)]
+pub struct Activity {
+    /// Frames left to highlight each key as pressed
+    pressed: [u8; 16],
+
+    /// Frames left to highlight each key as tested by `SKP` or `SKNP`
+    polled: [u8; 16],
+
+    /// Frames left to show that `LD Vx, K` is waiting for a key
+    waiting: u8,
+}
+
+impl Activity {
+    /// Highlight `key` as pressed.
+    pub fn press(&mut self, key: u8) {
+        self.pressed[key as usize & 0xF] = HIGHLIGHT;
+    }
+
+    /// Highlight `key` as tested by `SKP` or `SKNP`.
+    pub fn poll(&mut self, key: u8) {
+        if let Some(polled) = self.polled.get_mut(key as usize) { *polled = HIGHLIGHT }
+    }
+
+    /// Show that `LD Vx, K` is waiting for a key.
+    pub fn wait(&mut self) {
+        self.waiting = HIGHLIGHT;
+    }
+
+    /// Whether `key` was pressed recently.
+    pub fn is_pressed(&self, key: u8) -> bool {
+        self.pressed[key as usize & 0xF] > 0
+    }
+
+    /// Whether `key` was tested recently.
+    pub fn is_polled(&self, key: u8) -> bool {
+        self.polled[key as usize & 0xF] > 0
+    }
+
+    /// Whether `LD Vx, K` was waiting for a key recently.
+    pub fn is_waiting(&self) -> bool {
+        self.waiting > 0
+    }
+
+    /// Advance one 60Hz frame, fading out highlights.
+    pub fn tick(&mut self) {
+        for frames in self.pressed.iter_mut().chain(self.polled.iter_mut()) {
+            *frames = frames.saturating_sub(1);
+        }
+        self.waiting = self.waiting.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Keymap, HOTKEYS};
+
+    #[test]
+    fn default() {
+        assert_eq!("x123qweasdzc4tfv".parse::<Keymap>(), Ok(Keymap::default()));
+        assert_eq!(Keymap::default().key('t'), Some(0xD));
+    }
+
+    #[test]
+    fn hotkeys() {
+        for &hotkey in HOTKEYS.iter() {
+            let keymap = format!("x123qweasdzc4{}fv", hotkey);
+            assert_eq!(keymap.parse::<Keymap>(), Err(format!("Invalid keymap `{}`: `{}` is a hotkey", keymap, hotkey)));
+        }
+        assert!("x123qweasdzc4ffv".parse::<Keymap>().unwrap_err().contains("bound twice"));
+    }
+}
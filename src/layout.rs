@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+
+/// Panel of the terminal UI, besides the display.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Panel {
+    /// Registers, timers, and program counter, below the display
+    Registers,
+
+    /// Instructions around the program counter, right of the display
+    Disassembly,
+
+    /// Hex dump of memory around I, right of the display
+    Memory,
+
+    /// Return addresses on the stack, right of the display
+    Stack,
+
+    /// CHIP-8 keys and their keyboard bindings, below the display
+    Keypad,
+}
+
+impl Panel {
+    /// All panels, in placement order.
+    pub const ALL: [Panel; 5] = [Panel::Registers, Panel::Disassembly, Panel::Memory, Panel::Stack, Panel::Keypad];
+
+    /// Terminal columns taken, and rows if fixed.
+    fn size(self) -> (u16, Option<u16>) {
+        match self {
+        | Panel::Registers => (67, Some(5)),
+        | Panel::Keypad => (23, Some(5)),
+        | Panel::Disassembly => (26, None),
+        | Panel::Stack => (10, None),
+        | Panel::Memory => (31, None),
+        }
+    }
+
+    /// Whether the panel goes below the display rather than right of it.
+    fn below(self) -> bool {
+        self.size().1.is_some()
+    }
+}
+
+impl std::str::FromStr for Panel {
+    type Err = String;
+    fn from_str(panel: &str) -> Result<Self, Self::Err> {
+        match panel {
+        | "registers" => Ok(Panel::Registers),
+        | "disassembly" => Ok(Panel::Disassembly),
+        | "memory" => Ok(Panel::Memory),
+        | "stack" => Ok(Panel::Stack),
+        | "keypad" => Ok(Panel::Keypad),
+        | _ => Err(format!("Unknown panel `{}`: expected `registers`, `disassembly`, `memory`, `stack`, or `keypad`", panel)),
+        }
+    }
+}
+
+/// Rectangle of the terminal, with 0-based offsets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub w: u16,
+    pub h: u16,
+}
+
+/// Positions of the display, visible panels, and status message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Placement {
+    /// Offset of the display
+    pub display: (u16, u16),
+
+    /// Panels that fit in the terminal
+    pub panels: Vec<(Panel, Rect)>,
+
+    /// Status message line, if it fits
+    pub message: Option<Rect>,
+}
+
+/// Arrangement of the terminal UI.
+///
+/// Panels below the display are stacked under it in the left column,
+/// and the rest fill columns to its right. Panels that don't fit in the
+/// terminal are left out rather than drawn over each other.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    /// Terminal size in columns and rows
+    size: (u16, u16),
+
+    /// Panels to show
+    panels: HashSet<Panel>,
+
+    /// Whether to show only the display, centered in the terminal
+    game: bool,
+}
+
+impl Layout {
+    /// Lay out `panels` in a terminal of `size` columns and rows.
+    pub fn new(size: (u16, u16), panels: &[Panel]) -> Self {
+        Layout {
+            size,
+            panels: panels.iter().cloned().collect(),
+            game: false,
+        }
+    }
+
+    /// Change the terminal size.
+    pub fn resize(&mut self, size: (u16, u16)) {
+        self.size = size;
+    }
+
+    /// Show or hide `panel`.
+    pub fn toggle(&mut self, panel: Panel) {
+        if !self.panels.remove(&panel) {
+            self.panels.insert(panel);
+        }
+    }
+
+    /// Switch between showing only the centered display and showing panels.
+    pub fn toggle_game(&mut self) {
+        self.game = !self.game;
+    }
+
+    /// Place everything around a display of `display` columns and rows.
+    pub(crate) fn place(&self, display: (u16, u16)) -> Placement {
+        let (cols, rows) = self.size;
+        let (dw, dh) = display;
+
+        if self.game {
+            let x = cols.saturating_sub(dw) / 2;
+            let y = rows.saturating_sub(dh) / 2;
+            let message = if y + dh < rows { Some(Rect { x: 0, y: rows - 1, w: cols, h: 1 }) } else { None };
+            return Placement { display: (x, y), panels: Vec::new(), message };
+        }
+
+        let mut panels = Vec::new();
+        let mut y = dh + 1;
+        let mut left = dw;
+        for panel in Panel::ALL.iter().filter(|panel| panel.below() && self.panels.contains(panel)) {
+            let (w, h) = panel.size();
+            let h = h.unwrap_or(0);
+            if y + h >= rows { continue }
+            panels.push((*panel, Rect { x: 0, y, w, h }));
+            left = left.max(w);
+            y += h + 1;
+        }
+
+        let mut x = left + 2;
+        for panel in Panel::ALL.iter().filter(|panel| !panel.below() && self.panels.contains(panel)) {
+            let (w, _) = panel.size();
+            if x + w > cols { continue }
+            panels.push((*panel, Rect { x, y: 0, w, h: rows }));
+            x += w + 2;
+        }
+
+        let w = if x == left + 2 { cols } else { left };
+        let message = if y < rows { Some(Rect { x: 0, y, w, h: 1 }) } else { None };
+
+        Placement { display: (0, 0), panels, message }
+    }
+}
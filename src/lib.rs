@@ -1,8 +1,65 @@
+//! CHIP-8 emulator with a terminal UI and debugger.
+//!
+//! The emulator core can also be embedded: configure a `Chip` with `Chip::builder`,
+//! run it a frame at a time with `Chip::frame` or an instruction at a time with
+//! `Chip::step`, and inspect it through `Chip::cpu`, `Chip::mem`, `Chip::stack`,
+//! and `Chip::display`.
+//!
+//! The interpreter itself lives in the `no_std` `chip-core` crate, for hosts
+//! without a terminal, such as microcontrollers.
+//!
+//! ```
+//! use chip::{Asm, Chip, Display};
+//!
+//! // Draw the font sprite for 0 at (0, 0)
+//! let rom = vec![0xA0, 0x00, 0xD0, 0x05];
+//! let mut chip = Chip::builder().cycles(2).seed(0).build(rom);
+//! chip.frame().unwrap();
+//!
+//! assert_eq!(chip.display().rows()[0] >> 60, 0xF);
+//! assert_eq!(chip.cpu().pc, chip::Addr::from(0x204));
+//!
+//! let mem = chip.mem();
+//! let op = Asm::parse(mem[0x200.into()], mem[0x201.into()]).unwrap();
+//! assert_eq!(op.to_string(), "LD I, 0x000");
+//! assert_eq!(Display::WIDTH, 64);
+//! ```
+
+#![allow(clippy::upper_case_acronyms)]
+
 mod chip;
-mod cpu;
+mod color;
 mod display;
-mod ram;
-mod asm;
-mod stack;
+mod listing;
+mod profile;
+mod watch;
+mod meta;
+mod capture;
+mod layout;
+mod keypad;
+mod testing;
+mod script;
+
+use chip_core::{asm, cpu, fault, input, machine, quirks, ram, trace};
+
+pub mod dap;
+pub mod fuzz;
 
-pub use chip::Chip;
+pub use asm::{Asm, AsmError};
+pub use capture::{screenshot, Recorder, MAX_SCALE};
+pub use chip::{Builder, Chip, Hit, Options};
+pub use color::{Depth, Palette, Rgb};
+pub use cpu::{CPU, Reg};
+pub use display::{Display, Render};
+pub use fault::{Fault, Policy, UnknownPolicy};
+pub use keypad::Keymap;
+pub use layout::{Layout, Panel};
+pub use meta::Meta;
+pub use profile::Profile;
+pub use machine::{Host, Machine};
+pub use quirks::{Quirks, UnknownQuirks};
+pub use ram::{Addr, Mem, Overlap, FONT_SIZE};
+pub use script::{Script, ScriptError};
+pub use testing::ChipTest;
+pub use trace::Trace;
+pub use watch::{Warning, Watch};
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::path;
+
+use crate::ram;
+
+/// Assembler listing mapping source lines to addresses.
+///
+/// Any line whose first token is a hexadecimal address is treated as
+/// assembled code at that address: either prefixed with `0x` or `$` and
+/// optionally followed by `:`, or three or four digits followed by `:`
+/// with at least one decimal digit, so labels and mnemonics like `bad:`
+/// or `ADD` are not mistaken for addresses. All other lines are ignored,
+/// so most assembler listing formats work as-is:
+///
+/// ```text
+/// 0x200: 00E0    CLS
+/// 202:   6A02    LD VA, 0x02
+/// ```
+#[derive(Clone, Debug)]
+pub struct Listing {
+    /// Path to the listing file
+    path: path::PathBuf,
+
+    /// Address of each source line
+    addrs: HashMap<u32, ram::Addr>,
+
+    /// First source line of each address
+    lines: HashMap<ram::Addr, u32>,
+}
+
+impl Listing {
+    /// Load a listing from `path`.
+    pub fn load<P: Into<path::PathBuf>>(path: P) -> std::io::Result<Self> {
+        let path = path.into();
+        let text = std::fs::read_to_string(&path)?;
+        let mut addrs = HashMap::new();
+        let mut lines = HashMap::new();
+        for (line, addr) in text.lines()
+            .enumerate()
+            .filter_map(|(line, text)| Some((line as u32 + 1, parse(text)?)))
+        {
+            addrs.insert(line, addr);
+            lines.entry(addr).or_insert(line);
+        }
+        Ok(Listing { path, addrs, lines })
+    }
+
+    /// Path to the listing file.
+    pub fn path(&self) -> &path::Path {
+        &self.path
+    }
+
+    /// Address assembled from 1-based source `line`.
+    pub fn addr(&self, line: u32) -> Option<ram::Addr> {
+        self.addrs.get(&line).cloned()
+    }
+
+    /// 1-based source line assembled at `addr`.
+    pub fn line(&self, addr: ram::Addr) -> Option<u32> {
+        self.lines.get(&addr).cloned()
+    }
+}
+
+/// Parse the leading address of a listing line.
+fn parse(line: &str) -> Option<ram::Addr> {
+    let token = line.split_whitespace().next()?;
+    let digits = match token.strip_prefix("0x").or_else(|| token.strip_prefix('$')) {
+    | Some(digits) => digits.strip_suffix(':').unwrap_or(digits),
+    | None => token
+        .strip_suffix(':')
+        .filter(|digits| (3..=4).contains(&digits.len()) && digits.bytes().any(|byte| byte.is_ascii_digit()))?,
+    };
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) { return None }
+    match u16::from_str_radix(digits, 16) {
+    | Ok(addr) if addr <= 0x0FFF => Some(addr.into()),
+    | _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn prefixed() {
+        assert_eq!(parse("0x200: 00E0    CLS"), Some(0x200.into()));
+        assert_eq!(parse("$2A4 6A02"), Some(0x2A4.into()));
+        assert_eq!(parse("  0xFFF:"), Some(0xFFF.into()));
+        assert_eq!(parse("0x1000: 00E0"), None);
+        assert_eq!(parse("0x: 00E0"), None);
+        assert_eq!(parse("0x+20: 00E0"), None);
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(parse("202:   6A02    LD VA, 0x02"), Some(0x202.into()));
+        assert_eq!(parse("0A00: 00E0"), Some(0xA00.into()));
+        assert_eq!(parse("202 6A02"), None);
+        assert_eq!(parse("20: 00E0"), None);
+    }
+
+    #[test]
+    fn mnemonics() {
+        for line in ["ADD V0, 1", "DB 0xFF", "bad:", "cafe", "fade: JP fade", "dec:", "; 0x200", ""] {
+            assert_eq!(parse(line), None, "{:?}", line);
+        }
+    }
+}
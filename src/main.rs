@@ -1,4 +1,6 @@
 use std::io::Write;
+use std::sync::atomic;
+use std::sync::Arc;
 use std::time;
 use std::path;
 
@@ -6,8 +8,9 @@ use structopt::StructOpt;
 
 use termion::cursor;
 use termion::clear;
-use termion::event::{Event, Key};
-use termion::input::TermRead;
+use termion::style;
+use termion::event::{Event, Key, MouseButton, MouseEvent};
+use termion::input::{MouseTerminal, TermRead};
 use termion::raw::IntoRawMode;
 
 /// 1s
@@ -23,14 +26,190 @@ struct Opt {
     #[structopt(long = "hz", default_value = "1000")]
     hz: u32,
 
+    /// How to render pixels: `block`, `half` (two pixels per cell), `braille`
+    /// (eight pixels per cell), or `square` (two columns per pixel).
+    #[structopt(long = "render", default_value = "block")]
+    render: chip::Render,
+
+    /// Preset colors: `default` (terminal colors), `green`, `amber`, `lcd`, or `inverted`.
+    /// Overrides the ROM's metadata file.
+    #[structopt(long = "palette")]
+    palette: Option<String>,
+
+    /// Color of set pixels, e.g. `#33FF66`. Overrides the palette.
+    #[structopt(long = "fg")]
+    fg: Option<chip::Rgb>,
+
+    /// Color of unset pixels, e.g. `#000000`. Overrides the palette.
+    #[structopt(long = "bg")]
+    bg: Option<chip::Rgb>,
+
+    /// Panels to show: any of `registers`, `disassembly`, `memory`, `stack`, and `keypad`,
+    /// separated by commas. Toggle them while running with F1 to F5.
+    #[structopt(long = "panels", default_value = "registers,disassembly", raw(require_delimiter = "true"))]
+    panels: Vec<chip::Panel>,
+
+    /// Show only the display, centered in the terminal. Toggle while running with F6.
+    #[structopt(long = "game")]
+    game: bool,
+
+    /// Fade out unset pixels over this many frames at 60Hz, reducing sprite flicker.
+    #[structopt(long = "persist", default_value = "0")]
+    persist: u8,
+
+    /// Serve the Debug Adapter Protocol on this localhost port.
+    #[structopt(long = "dap")]
+    dap: Option<u16>,
+
+    /// Assembler listing used to map source lines to addresses.
+    #[structopt(long = "listing", parse(from_os_str))]
+    listing: Option<path::PathBuf>,
+
+    /// Write an execution profile and annotated disassembly to this file on exit.
+    #[structopt(long = "profile", parse(from_os_str))]
+    profile: Option<path::PathBuf>,
+
+    /// Number of hot loops to include in the profile.
+    #[structopt(long = "top", default_value = "10")]
+    top: usize,
+
+    /// Warn when executing code written at runtime, or overwriting executed code.
+    #[structopt(long = "smc")]
+    smc: bool,
+
+    /// Warn about `SYS` calls, executing interpreter-reserved memory below 0x200,
+    /// and writes to it or to the font data.
+    #[structopt(long = "strict")]
+    strict: bool,
+
+    /// Memory address in hexadecimal where font data is stored, e.g. 0x050.
+    #[structopt(long = "font", default_value = "0x000", parse(try_from_str = "parse_font"))]
+    font: u16,
+
+    /// Memory address in hexadecimal where the ROM is loaded and execution starts, e.g. 0x600.
+    #[structopt(long = "load", default_value = "0x200", parse(try_from_str = "parse_load"))]
+    load: u16,
+
+    /// Seed the random number generator, for reproducible runs.
+    #[structopt(long = "seed")]
+    seed: Option<u64>,
+
+    /// Pause when a warning is raised.
+    #[structopt(long = "break-on-warning")]
+    break_on_warning: bool,
+
+    /// Keyboard keys bound to CHIP-8 keys 0 through F, in order.
+    #[structopt(long = "keymap", default_value = "x123qweasdzc4tfv")]
+    keymap: chip::Keymap,
+
+    /// Interpreter behaviors to emulate: `default`, `vip`, `schip`, or `xochip`.
+    #[structopt(long = "quirks", default_value = "default")]
+    quirks: chip::Quirks,
+
+    /// Clip sprites at the screen edges instead of wrapping them around.
+    #[structopt(long = "clip")]
+    clip: bool,
+
+    /// How to handle stack overflow and underflow, and memory access beyond 0xFFF:
+    /// `trap` pauses, `wrap` wraps around like hardware, and `error` exits.
+    #[structopt(long = "fault", default_value = "trap")]
+    fault: chip::Policy,
+
+    /// Append warnings and faults to this file.
+    #[structopt(long = "log", parse(from_os_str))]
+    log: Option<path::PathBuf>,
+
+    /// Image pixels per display pixel in screenshots and recordings.
+    #[structopt(long = "scale", default_value = "8", parse(try_from_str = "parse_scale"))]
+    scale: u32,
+
+    /// Save the display to this PNG or PBM file on exit.
+    #[structopt(long = "screenshot", parse(from_os_str))]
+    screenshot: Option<path::PathBuf>,
+
+    /// Record the display into this animated GIF, or as numbered PNG files into this directory.
+    #[structopt(long = "record", parse(from_os_str))]
+    record: Option<path::PathBuf>,
+
+    /// Rhai script registering hooks on frames, instruction addresses, and memory writes.
+    #[structopt(long = "script", parse(from_os_str))]
+    script: Option<path::PathBuf>,
+
+    /// Run for this many 60Hz frames without the terminal UI, e.g. to take a screenshot.
+    #[structopt(long = "headless")]
+    headless: Option<u32>,
+
     /// Binary CHIP-8 ROM file to emulate.
     #[structopt(parse(from_os_str))]
     path: path::PathBuf,
 }
 
+/// Parse a hexadecimal address that leaves room for `size` bytes of `what`.
+fn parse_addr(addr: &str, size: u16, what: &str) -> Result<u16, String> {
+    match u16::from_str_radix(addr.trim_start_matches("0x"), 16) {
+    | Ok(addr) if addr <= 0x1000 - size => Ok(addr),
+    | Ok(_) => Err(format!("{} at `{}` does not fit in memory", what, addr)),
+    | Err(error) => Err(error.to_string()),
+    }
+}
+
+/// Parse a font data address.
+fn parse_font(addr: &str) -> Result<u16, String> {
+    parse_addr(addr, chip::FONT_SIZE, "Font data")
+}
+
+/// Parse a program load address that leaves room for at least one instruction.
+fn parse_load(addr: &str) -> Result<u16, String> {
+    parse_addr(addr, 2, "Program")
+}
+
+/// Parse an image scale small enough for every capture format.
+fn parse_scale(scale: &str) -> Result<u32, String> {
+    match scale.parse::<u32>() {
+    | Ok(scale) if scale <= chip::MAX_SCALE => Ok(scale),
+    | Ok(_) => Err(format!("Scale `{}` is larger than the maximum of {}", scale, chip::MAX_SCALE)),
+    | Err(error) => Err(error.to_string()),
+    }
+}
+
+/// First unused `<ROM name>-<n>.png` in the current directory.
+fn screenshot_path(rom: &path::Path) -> path::PathBuf {
+    let name = rom.file_stem().unwrap_or_default().to_string_lossy();
+    let mut n = 1;
+    loop {
+        let path = path::PathBuf::from(format!("{}-{}.png", name, n));
+        if !path.exists() { return path }
+        n += 1;
+    }
+}
+
+/// Restore the terminal's original mode, colors, cursor, and screen before
+/// reporting a panic, so the message isn't garbled or cleared afterwards.
+fn restore_on_panic() {
+    let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+    let saved = unsafe { libc::tcgetattr(libc::STDOUT_FILENO, &mut termios) } == 0;
+    let report = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if saved { unsafe { libc::tcsetattr(libc::STDOUT_FILENO, libc::TCSANOW, &termios); } }
+        print!("{}{}{}{}", style::Reset, clear::All, cursor::Goto(1, 1), cursor::Show);
+        std::io::stdout().flush().ok();
+        report(info);
+    }));
+}
+
+/// Clear the screen and draw everything again, e.g. after the layout changes.
+fn redraw<W: Write>(chip: &mut chip::Chip, layout: &chip::Layout, out: &mut W) -> std::io::Result<()> {
+    write!(out, "{}", clear::All)?;
+    chip.invalidate();
+    chip.draw(layout, out)?;
+    out.flush()
+}
+
 impl Drop for Opt {
     fn drop(&mut self) {
-        print!("{}{}{}", clear::All, cursor::Goto(0, 0), cursor::Show);
+        if self.headless.is_some() || std::thread::panicking() { return }
+        print!("{}{}{}", clear::All, cursor::Goto(1, 1), cursor::Show);
+        std::io::stdout().flush().ok();
     }
 }
 
@@ -38,52 +217,259 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Opt::from_args();
     let file = std::fs::read(&args.path)?;
 
-    let mut chip = chip::Chip::new(file);
-    let mut timer = time::Instant::now();
-    let restore = chip.clone();
+    let quirks = chip::Quirks { clip: args.quirks.clip || args.clip, ..args.quirks };
+    let mut builder = chip::Chip::builder()
+        .quirks(quirks)
+        .font(args.font.into())
+        .load(args.load.into())
+        .fault(args.fault)
+        .cycles((args.hz / 60).max(1))
+        .keymap(args.keymap);
+    if let Some(seed) = args.seed { builder = builder.seed(seed); }
+    let options = builder.options();
+    let mut chip = builder.try_build(file).map_err(|overlap| overlap.to_string())?;
+    chip.set_render(args.render);
 
-    let mut hz = args.hz;
-    let mut delay = SECOND / hz;
-    let mut fuel = -1;
+    let meta = chip::Meta::load(&args.path)?;
+    let mut palette = match args.palette.as_ref().or(meta.palette.as_ref()) {
+    | Some(name) => chip::Palette::preset(name)?,
+    | None => chip::Palette::default(),
+    };
+    // Colors from the metadata only apply over the metadata's own palette
+    let (fg, bg) = match args.palette {
+    | Some(_) => (None, None),
+    | None => (meta.fg, meta.bg),
+    };
+    palette.fg = args.fg.or(fg).or(palette.fg);
+    palette.bg = args.bg.or(bg).or(palette.bg);
+    chip.set_palette(palette, chip::Depth::detect());
+    chip.set_persist(args.persist);
 
-    let stdin = termion::async_stdin();
-    let mut stdout = std::io::stdout().into_raw_mode()?;
-    let mut stream = stdin.events();
+    let mut profile = args.profile.as_ref().map(|_| chip::Profile::default());
 
+    let mut watch = chip::Watch::default();
+    if args.smc { watch = watch.smc(); }
+    if args.strict { watch = watch.strict(options.font, options.load); }
 
-    write!(stdout, "{}{}", cursor::Hide, clear::All)?;
-    chip.draw(0, 0, &mut stdout)?;
+    let mut script = match &args.script {
+    | Some(path) => chip::Script::load(path)?,
+    | None => chip::Script::default(),
+    };
 
-    loop {
+    let mut log = match &args.log {
+    | Some(path) => Some(std::fs::OpenOptions::new().create(true).append(true).open(path)?),
+    | None => None,
+    };
+
+    let mut recorder = match &args.record {
+    | Some(path) => Some(chip::Recorder::create(path, args.scale, palette)?),
+    | None => None,
+    };
 
-        std::thread::sleep(delay);
-        fuel = if fuel <= 0 { fuel } else { fuel - 1 };
-
-        match stream.next() {
-        | Some(Ok(Event::Key(Key::Esc))) => break,
-        | Some(Ok(Event::Key(Key::Char(' ')))) => fuel = if fuel < 0 { 0 } else { -1 },
-        | Some(Ok(Event::Key(Key::Char('-')))) => { hz -= 10; delay = SECOND / hz; }
-        | Some(Ok(Event::Key(Key::Char('+')))) => { hz += 10; delay = SECOND / hz; }
-        | Some(Ok(Event::Key(Key::Char('n')))) if fuel >= 0 => fuel += 1,
-        | Some(Ok(Event::Key(Key::Char('r')))) => {
-            chip = restore.clone();
-            chip.draw(0, 0, &mut stdout)?;
-            stdout.flush()?;
-        },
-        | Some(Ok(Event::Key(key))) => chip.set_key(key),
-        | _ => (),
+    let mut failure = None;
+
+    if let Some(frames) = args.headless {
+        for _ in 0..frames {
+            let mut fault = None;
+            for _ in 0..options.cycles {
+                script.reached(&mut chip)?;
+                fault = chip.step_traced(&mut (&mut profile, (&mut watch, &mut script))).err();
+                script.written(&mut chip)?;
+                if fault.is_some() { break }
+            }
+            if fault.is_none() {
+                chip.tick();
+                script.frame(&mut chip)?;
+            }
+            script.drain().for_each(|text| println!("{}", text));
+            let warnings = watch.drain().collect::<Vec<_>>();
+            for text in warnings.iter().map(ToString::to_string) {
+                if let Some(log) = &mut log { writeln!(log, "{}", text)?; }
+                eprintln!("{}", text);
+            }
+            if fault.is_some() {
+                failure = fault;
+                break;
+            }
+            if args.break_on_warning && !warnings.is_empty() { break }
+            if let Some(recorder) = &mut recorder { recorder.frame(&chip)?; }
         }
+    } else {
+        let mut timer = time::Instant::now();
+        let restore = chip.clone();
 
-        if fuel == 0 { timer += delay; continue }
+        let mut hz = args.hz;
+        let mut delay = SECOND / hz;
 
-        if std::time::Instant::now() - timer > TICK {
-            timer = std::time::Instant::now();
-            chip.tick();
-            chip.draw(0, 0, &mut stdout)?;
-            stdout.flush()?;
+        let mut dap = match args.dap {
+        | None => None,
+        | Some(port) => {
+            println!("Waiting for debug adapter client on port {}...", port);
+            Some(chip::dap::Server::listen(port, args.listing.as_deref())?)
         }
+        };
+
+        let mut fuel = if dap.is_some() { 0 } else { -1 };
+
+        let mut layout = chip::Layout::new(termion::terminal_size()?, &args.panels);
+        if args.game { layout.toggle_game(); }
+
+        let resized = Arc::new(atomic::AtomicBool::new(false));
+        signal_hook::flag::register(signal_hook::consts::SIGWINCH, Arc::clone(&resized))?;
+
+        restore_on_panic();
+        let stdin = termion::async_stdin();
+        let mut stdout = MouseTerminal::from(std::io::stdout().into_raw_mode()?);
+        let mut stream = stdin.events();
+
+        // Key held down by clicking on the keypad panel
+        let mut clicked = None;
+
+        // Breakpoint just paused at, to step past when resuming
+        let mut paused = None;
+
+        write!(stdout, "{}{}", cursor::Hide, clear::All)?;
+        chip.draw(&layout, &mut stdout)?;
+
+        loop {
+
+            std::thread::sleep(delay);
+            fuel = if fuel <= 0 { fuel } else { fuel - 1 };
+
+            if resized.swap(false, atomic::Ordering::Relaxed) {
+                layout.resize(termion::terminal_size()?);
+                redraw(&mut chip, &layout, &mut stdout)?;
+            }
+
+            match stream.next() {
+            | Some(Ok(Event::Key(Key::Esc))) => break,
+            | Some(Ok(Event::Key(Key::Char(' ')))) => fuel = if fuel < 0 { 0 } else { -1 },
+            | Some(Ok(Event::Key(Key::Char('-')))) => { hz -= 10; delay = SECOND / hz; }
+            | Some(Ok(Event::Key(Key::Char('+')))) => { hz += 10; delay = SECOND / hz; }
+            | Some(Ok(Event::Key(Key::Char('n')))) if fuel >= 0 => fuel += 1,
+            | Some(Ok(Event::Key(Key::Char('r')))) => {
+                let breakpoints = chip.breakpoints().clone();
+                chip = restore.clone();
+                for addr in breakpoints { chip.toggle_breakpoint(addr); }
+                chip.draw(&layout, &mut stdout)?;
+                stdout.flush()?;
+            },
+            | Some(Ok(Event::Key(Key::F(n)))) if (1..=6).contains(&n) => {
+                match n {
+                | 6 => layout.toggle_game(),
+                | n => layout.toggle(chip::Panel::ALL[n as usize - 1]),
+                }
+                redraw(&mut chip, &layout, &mut stdout)?;
+            }
+            | Some(Ok(Event::Key(Key::Char('p')))) => {
+                let path = screenshot_path(&args.path);
+                chip::screenshot(&chip, &path, args.scale, palette)?;
+                chip.message(&layout, &format!("Saved screenshot to {}", path.display()), &mut stdout)?;
+                stdout.flush()?;
+            }
+            | Some(Ok(Event::Key(key))) => chip.set_key(key),
+            | Some(Ok(Event::Mouse(MouseEvent::Press(MouseButton::Left, x, y)))) => {
+                match chip.hit(&layout, x, y) {
+                | Some(chip::Hit::Key(key)) => {
+                    chip.press(key);
+                    clicked = Some(key);
+                }
+                | Some(chip::Hit::Addr(addr)) => {
+                    let set = chip.toggle_breakpoint(addr);
+                    let verb = if set { "Set" } else { "Cleared" };
+                    chip.message(&layout, &format!("{} breakpoint at {}", verb, addr), &mut stdout)?;
+                }
+                | None => (),
+                }
+                chip.draw(&layout, &mut stdout)?;
+                stdout.flush()?;
+            }
+            | Some(Ok(Event::Mouse(MouseEvent::Release(..)))) => {
+                if let Some(key) = clicked.take() { chip.release(key); }
+            }
+            | _ => (),
+            }
+
+            if let Some(server) = &mut dap {
+                match server.poll(&chip)? {
+                | Some(chip::dap::Command::Pause) => fuel = 0,
+                | Some(chip::dap::Command::Resume) => fuel = -1,
+                | Some(chip::dap::Command::Disconnect) => break,
+                | None => (),
+                }
+            }
+
+            if fuel == 0 { timer += delay; continue }
+
+            if std::time::Instant::now() - timer > TICK {
+                timer = std::time::Instant::now();
+                chip.tick();
+                script.frame(&mut chip)?;
+                if let Some(recorder) = &mut recorder { recorder.frame(&chip)?; }
+                chip.draw(&layout, &mut stdout)?;
+                stdout.flush()?;
+            }
+
+            if let Some(server) = &mut dap {
+                if server.stop(&chip)? { fuel = 0; continue }
+            }
+
+            if chip.breakpoints().contains(&chip.pc()) && paused != Some(chip.pc()) {
+                fuel = 0;
+                paused = Some(chip.pc());
+                chip.draw(&layout, &mut stdout)?;
+                chip.message(&layout, &format!("Breakpoint at {}", chip.pc()), &mut stdout)?;
+                stdout.flush()?;
+                continue;
+            }
+            paused = None;
+
+            script.reached(&mut chip)?;
+            let fault = chip.step_traced(&mut (&mut profile, (&mut watch, &mut script))).err();
+            script.written(&mut chip)?;
+
+            if let (Some(fault), chip::Policy::Error) = (fault, args.fault) {
+                failure = Some(fault);
+                break;
+            }
+
+            let warnings = watch.drain().collect::<Vec<_>>();
+            let stop = fault.is_some() || args.break_on_warning && !warnings.is_empty();
+
+            for text in script.drain() {
+                chip.message(&layout, &text, &mut stdout)?;
+                stdout.flush()?;
+            }
+
+            let mut message = None;
+            for text in warnings.iter().map(ToString::to_string).chain(fault.map(|fault| fault.to_string())) {
+                if let Some(log) = &mut log { writeln!(log, "{}", text)?; }
+                chip.message(&layout, &text, &mut stdout)?;
+                stdout.flush()?;
+                message = Some(text);
+            }
+
+            if let (true, Some(message)) = (stop, message) {
+                fuel = 0;
+                if let Some(server) = &mut dap { server.interrupt(&message)?; }
+            }
+        }
+    }
+
+    if let (Some(path), Some(profile)) = (&args.profile, &profile) {
+        profile.report(&chip, args.top, &mut std::fs::File::create(path)?)?;
+    }
+
+    if let Some(path) = &args.screenshot {
+        chip::screenshot(&chip, path, args.scale, palette)?;
+    }
+
+    if let Some(recorder) = recorder {
+        recorder.finish()?;
+    }
 
-        chip.step();
+    if let Some(fault) = failure {
+        return Err(fault.to_string().into());
     }
 
     Ok(())
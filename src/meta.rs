@@ -0,0 +1,54 @@
+use std::io;
+use std::path;
+
+use serde_json::Value;
+
+use crate::color;
+
+/// Per-ROM settings, read from a JSON file next to the ROM
+/// with its extension replaced by `.json`, e.g. `rom/PONG.json`:
+///
+/// ```json
+/// { "palette": "amber", "fg": "#FFB000", "bg": "#000000" }
+/// ```
+///
+/// All fields are optional.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Meta {
+    /// Preset palette name
+    pub palette: Option<String>,
+
+    /// Color of set pixels
+    pub fg: Option<color::Rgb>,
+
+    /// Color of unset pixels
+    pub bg: Option<color::Rgb>,
+}
+
+impl Meta {
+    /// Load the metadata for the ROM at `rom`, or the default if there is none.
+    pub fn load(rom: &path::Path) -> io::Result<Self> {
+        let path = rom.with_extension("json");
+        let text = match std::fs::read(&path) {
+        | Ok(text) => text,
+        | Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Meta::default()),
+        | Err(error) => return Err(error),
+        };
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), message));
+        let json = serde_json::from_slice::<Value>(&text).map_err(|error| invalid(error.to_string()))?;
+        let string = |key: &str| match json.get(key) {
+        | None | Some(Value::Null) => Ok(None),
+        | Some(Value::String(value)) => Ok(Some(value.clone())),
+        | Some(_) => Err(invalid(format!("`{}` must be a string", key))),
+        };
+        let rgb = |key: &str| match string(key)? {
+        | None => Ok(None),
+        | Some(value) => value.parse().map(Some).map_err(invalid),
+        };
+        Ok(Meta {
+            palette: string("palette")?,
+            fg: rgb("fg")?,
+            bg: rgb("bg")?,
+        })
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::io;
+
+use crate::asm;
+use crate::chip;
+use crate::ram;
+use crate::trace;
+
+/// Byte was fetched as part of an instruction.
+const EXEC: u8 = 0b001;
+
+/// Byte was read as data.
+const READ: u8 = 0b010;
+
+/// Byte was written.
+const WRITE: u8 = 0b100;
+
+/// Execution counts and memory access map of a running ROM.
+#[derive(Clone, Debug)]
+pub struct Profile {
+    /// Execution count of each instruction address
+    hits: Vec<u64>,
+
+    /// Access flags of each byte
+    access: Vec<u8>,
+
+    /// Execution count of each opcode variant
+    ops: HashMap<&'static str, u64>,
+
+    /// Backward jumps taken, keyed by target and source address
+    loops: HashMap<(ram::Addr, ram::Addr), u64>,
+
+    /// Most recently executed instruction
+    last: Option<(ram::Addr, asm::Asm)>,
+}
+
+impl Profile {
+    /// Write a report with per-opcode counts, the `top` hottest loops,
+    /// an annotated disassembly of `chip`'s memory, and a code/data map.
+    pub fn report<W: io::Write>(&self, chip: &chip::Chip, top: usize, out: &mut W) -> io::Result<()> {
+        let total = self.hits.iter().sum::<u64>();
+        let percent = |count: u64| if total == 0 { 0.0 } else { count as f64 * 100.0 / total as f64 };
+
+        writeln!(out, "Executed {} instructions", total)?;
+
+        writeln!(out)?;
+        writeln!(out, "Instructions by opcode:")?;
+        let mut ops = self.ops.iter().collect::<Vec<_>>();
+        ops.sort_by(|(a, m), (b, n)| n.cmp(m).then(a.cmp(b)));
+        for (name, count) in ops {
+            writeln!(out, "    {:<6}{:>12}{:>9.2}%", name, count, percent(*count))?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "Hot loops:")?;
+        let mut loops = self.loops
+            .iter()
+            .map(|(&(start, end), &iterations)| (start, end, iterations, self.cost(start, end)))
+            .collect::<Vec<_>>();
+        loops.sort_by(|a, b| b.3.cmp(&a.3).then(a.0.cmp(&b.0)));
+        for (rank, (start, end, iterations, cost)) in loops.into_iter().take(top).enumerate() {
+            writeln!(
+                out,
+                "    {:>2}. {}..={}{:>12} iterations{:>14} instructions{:>9.2}%",
+                rank + 1,
+                start,
+                end,
+                iterations,
+                cost,
+                percent(cost),
+            )?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "Annotated disassembly:")?;
+        self.disassemble(chip, out)?;
+
+        writeln!(out)?;
+        writeln!(out, "Memory map:")?;
+        let mut start = 0;
+        for addr in 1..=self.access.len() {
+            if addr < self.access.len() && self.access[addr] == self.access[start] { continue }
+            if self.access[start] != 0 {
+                let (lo, hi) = (ram::Addr::from(start as u16), ram::Addr::from(addr as u16 - 1));
+                let kind = if self.access[start] & EXEC > 0 { "code" } else { "data" };
+                writeln!(out, "    {}..={}  {}  {}", lo, hi, flags(self.access[start]), kind)?;
+            }
+            start = addr;
+        }
+
+        Ok(())
+    }
+
+    /// Write executed instructions with hit counts, and accessed data bytes.
+    fn disassemble<W: io::Write>(&self, chip: &chip::Chip, out: &mut W) -> io::Result<()> {
+        let mut skipped = 0;
+        let mut addr = 0;
+        while addr < self.access.len() {
+            let here = ram::Addr::from(addr as u16);
+            if self.access[addr] == 0 {
+                skipped += 1;
+                addr += 1;
+                continue;
+            }
+            if skipped > 0 {
+                writeln!(out, "    ...    {} untouched bytes", skipped)?;
+                skipped = 0;
+            }
+            match here.offset(1) {
+            | Some(next) if self.hits[addr] > 0 => {
+                let (hi, lo) = (chip.machine.ram[here], chip.machine.ram[next]);
+                let op = asm::Asm::parse(hi, lo)
+                    .map(|op| op.to_string())
+                    .unwrap_or_else(|| String::from("???"));
+                writeln!(out, "    {}  {:02X}{:02X}  {}{:>12}  {}", here, hi, lo, flags(self.access[addr]), self.hits[addr], op)?;
+                addr += 2;
+            }
+            | _ => {
+                let byte = chip.machine.ram[here];
+                writeln!(out, "    {}  {:02X}    {}{:>12}  db {:#04X}", here, byte, flags(self.access[addr]), "", byte)?;
+                addr += 1;
+            }
+            }
+        }
+        if skipped > 0 {
+            writeln!(out, "    ...    {} untouched bytes", skipped)?;
+        }
+        Ok(())
+    }
+
+    /// Total instructions executed between `start` and `end` inclusive.
+    fn cost(&self, start: ram::Addr, end: ram::Addr) -> u64 {
+        self.hits[u16::from(start) as usize..=u16::from(end) as usize].iter().sum()
+    }
+}
+
+/// Render access flags as `XRW`, with `.` for unset flags.
+fn flags(access: u8) -> String {
+    [(EXEC, 'X'), (READ, 'R'), (WRITE, 'W')]
+        .iter()
+        .map(|(flag, c)| if access & flag > 0 { *c } else { '.' })
+        .collect()
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile {
+            hits: vec![0; 4096],
+            access: vec![0; 4096],
+            ops: HashMap::new(),
+            loops: HashMap::new(),
+            last: None,
+        }
+    }
+}
+
+impl trace::Trace for Profile {
+    fn exec(&mut self, addr: ram::Addr, op: asm::Asm) {
+        let index = u16::from(addr) as usize;
+        self.hits[index] += 1;
+        self.access[index] |= EXEC;
+        if let Some(next) = self.access.get_mut(index + 1) { *next |= EXEC }
+        *self.ops.entry(op.name()).or_insert(0) += 1;
+        if let Some((from, asm::Asm::JP(_))) | Some((from, asm::Asm::JO(_))) = self.last {
+            if addr <= from { *self.loops.entry((addr, from)).or_insert(0) += 1 }
+        }
+        self.last = Some((addr, op));
+    }
+
+    fn read(&mut self, addr: ram::Addr) {
+        self.access[u16::from(addr) as usize] |= READ;
+    }
+
+    fn write(&mut self, addr: ram::Addr, _: u8) {
+        self.access[u16::from(addr) as usize] |= WRITE;
+    }
+}
@@ -0,0 +1,304 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::path;
+use std::rc::Rc;
+
+use rhai::{Dynamic, Engine, EvalAltResult, FnPtr, AST, INT};
+
+use crate::chip;
+use crate::machine;
+use crate::ram;
+use crate::trace;
+
+/// Most Rhai operations per run of the top-level code or a hook, so a runaway
+/// hook fails instead of hanging the emulator.
+const OPERATIONS: u64 = 1_000_000;
+
+/// Error compiling or running a script.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptError(String);
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "Script error: {}", self.0)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+impl From<Box<EvalAltResult>> for ScriptError {
+    fn from(error: Box<EvalAltResult>) -> Self {
+        ScriptError(error.to_string())
+    }
+}
+
+/// Hooks registered by a script, and the emulator state its functions access.
+struct State {
+    /// Called after each 60Hz frame
+    frame: Vec<FnPtr>,
+
+    /// Called before executing the instruction at each address
+    pc: HashMap<ram::Addr, Vec<FnPtr>>,
+
+    /// Called with the byte written after an instruction writes to each address
+    write: HashMap<ram::Addr, Vec<FnPtr>>,
+
+    /// Interpreter state, swapped in from the emulator while hooks run
+    machine: machine::Machine,
+
+    /// Keys held down when hooks started, one bit per key
+    held: u16,
+
+    /// Keys pressed (`true`) or released (`false`) by hooks
+    keys: Vec<(u8, bool)>,
+
+    /// Printed messages not yet drained
+    messages: Vec<String>,
+}
+
+/// [Rhai](https://rhai.rs) script driving a `Chip`, e.g. a bot, a cheat, or an auto-tester.
+///
+/// The script's top-level code runs once when compiled, and registers hooks:
+///
+/// - `on_frame(|| ...)` after each 60Hz frame
+/// - `on_pc(addr, || ...)` before executing the instruction at `addr`
+/// - `on_write(addr, |byte| ...)` after an instruction writes `byte` to `addr`
+///
+/// Hooks can read and change the emulator with `reg(x)`, `set_reg(x, byte)`, `pc()`,
+/// `set_pc(addr)`, `i()`, `set_i(addr)`, `peek(addr)`, `poke(addr, byte)`, `pixel(x, y)`,
+/// `held(key)`, `press(key)`, and `release(key)`. Printed messages are kept for `Script::drain`.
+///
+/// The emulator calls `Script::reached` before each instruction, `Script::written`
+/// after it (with the script tracing it), and `Script::frame` after each frame.
+///
+/// ```
+/// use chip::{Chip, Script};
+///
+/// // Decrement V0 in a loop, which the script resets to 3 at 0
+/// let mut chip = Chip::builder().seed(0).build(vec![0x70, 0xFF, 0x12, 0x00]);
+/// let mut script = Script::compile(r#"
+///     on_pc(0x200, || if reg(0) == 0 { set_reg(0, 3) });
+///     on_frame(|| print(`V0 is ${reg(0)}`));
+/// "#).unwrap();
+///
+/// for _ in 0..2 {
+///     script.reached(&mut chip).unwrap();
+///     chip.step_traced(&mut script).unwrap();
+///     script.written(&mut chip).unwrap();
+/// }
+/// chip.tick();
+/// script.frame(&mut chip).unwrap();
+///
+/// assert_eq!(chip.cpu().reg[0], 2);
+/// assert_eq!(script.drain().collect::<Vec<_>>(), ["V0 is 2"]);
+/// ```
+pub struct Script {
+    /// Engine with the emulator functions registered
+    engine: Engine,
+
+    /// Compiled script, including its hooks
+    ast: AST,
+
+    /// Hooks and emulator state shared with the registered functions
+    state: Rc<RefCell<State>>,
+
+    /// Writes to hooked addresses by the last instruction
+    writes: Vec<(ram::Addr, u8)>,
+}
+
+impl Script {
+    /// Compile the script at `path` and run its top-level code.
+    pub fn load(path: &path::Path) -> Result<Self, ScriptError> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|error| ScriptError(format!("{}: {}", path.display(), error)))?;
+        Self::compile(&source).map_err(|ScriptError(error)| ScriptError(format!("{}: {}", path.display(), error)))
+    }
+
+    /// Compile `source` and run its top-level code.
+    pub fn compile(source: &str) -> Result<Self, ScriptError> {
+        let state = Rc::new(RefCell::new(State {
+            frame: Vec::new(),
+            pc: HashMap::new(),
+            write: HashMap::new(),
+            machine: machine::Machine::new(std::iter::empty(), machine::Options::default()),
+            held: 0,
+            keys: Vec::new(),
+            messages: Vec::new(),
+        }));
+        let engine = Self::engine(&state);
+        let ast = engine.compile(source).map_err(|error| ScriptError(error.to_string()))?;
+        engine.run_ast(&ast)?;
+        Ok(Script { engine, ast, state, writes: Vec::new() })
+    }
+
+    /// Engine with the hook and emulator functions registered over `state`.
+    fn engine(state: &Rc<RefCell<State>>) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(OPERATIONS);
+
+        let shared = Rc::clone(state);
+        engine.on_print(move |message| shared.borrow_mut().messages.push(message.to_string()));
+
+        let shared = Rc::clone(state);
+        engine.register_fn("on_frame", move |hook: FnPtr| shared.borrow_mut().frame.push(hook));
+        let shared = Rc::clone(state);
+        engine.register_fn("on_pc", move |at: INT, hook: FnPtr| -> Result<(), Box<EvalAltResult>> {
+            shared.borrow_mut().pc.entry(addr(at)?).or_default().push(hook);
+            Ok(())
+        });
+        let shared = Rc::clone(state);
+        engine.register_fn("on_write", move |at: INT, hook: FnPtr| -> Result<(), Box<EvalAltResult>> {
+            shared.borrow_mut().write.entry(addr(at)?).or_default().push(hook);
+            Ok(())
+        });
+
+        let shared = Rc::clone(state);
+        engine.register_fn("reg", move |x: INT| -> Result<INT, Box<EvalAltResult>> {
+            Ok(shared.borrow().machine.cpu.reg[nibble(x, "Register")? as usize] as INT)
+        });
+        let shared = Rc::clone(state);
+        engine.register_fn("set_reg", move |x: INT, value: INT| -> Result<(), Box<EvalAltResult>> {
+            shared.borrow_mut().machine.cpu.reg[nibble(x, "Register")? as usize] = byte(value)?;
+            Ok(())
+        });
+        let shared = Rc::clone(state);
+        engine.register_fn("pc", move || u16::from(shared.borrow().machine.cpu.pc) as INT);
+        let shared = Rc::clone(state);
+        engine.register_fn("set_pc", move |at: INT| -> Result<(), Box<EvalAltResult>> {
+            shared.borrow_mut().machine.cpu.pc = addr(at)?;
+            Ok(())
+        });
+        let shared = Rc::clone(state);
+        engine.register_fn("i", move || u16::from(shared.borrow().machine.cpu.idx) as INT);
+        let shared = Rc::clone(state);
+        engine.register_fn("set_i", move |at: INT| -> Result<(), Box<EvalAltResult>> {
+            shared.borrow_mut().machine.cpu.idx = addr(at)?;
+            Ok(())
+        });
+        let shared = Rc::clone(state);
+        engine.register_fn("peek", move |at: INT| -> Result<INT, Box<EvalAltResult>> {
+            Ok(shared.borrow().machine.ram[addr(at)?] as INT)
+        });
+        let shared = Rc::clone(state);
+        engine.register_fn("poke", move |at: INT, value: INT| -> Result<(), Box<EvalAltResult>> {
+            shared.borrow_mut().machine.ram[addr(at)?] = byte(value)?;
+            Ok(())
+        });
+        let shared = Rc::clone(state);
+        engine.register_fn("pixel", move |x: INT, y: INT| match (u8::try_from(x), u8::try_from(y)) {
+        | (Ok(x), Ok(y)) => shared.borrow().machine.display.get(x, y),
+        | _ => false,
+        });
+
+        let shared = Rc::clone(state);
+        engine.register_fn("held", move |key: INT| -> Result<bool, Box<EvalAltResult>> {
+            Ok(shared.borrow().held & (1 << nibble(key, "Key")?) > 0)
+        });
+        let shared = Rc::clone(state);
+        engine.register_fn("press", move |key: INT| -> Result<(), Box<EvalAltResult>> {
+            shared.borrow_mut().keys.push((nibble(key, "Key")?, true));
+            Ok(())
+        });
+        let shared = Rc::clone(state);
+        engine.register_fn("release", move |key: INT| -> Result<(), Box<EvalAltResult>> {
+            shared.borrow_mut().keys.push((nibble(key, "Key")?, false));
+            Ok(())
+        });
+
+        engine
+    }
+
+    /// Call the `on_pc` hooks for the instruction `chip` is about to execute.
+    pub fn reached(&mut self, chip: &mut chip::Chip) -> Result<(), ScriptError> {
+        let hooks = match self.state.borrow().pc.get(&chip.pc()) {
+        | None => return Ok(()),
+        | Some(hooks) => hooks.clone(),
+        };
+        self.call(chip, hooks.into_iter().map(|hook| (hook, Vec::new())))
+    }
+
+    /// Call the `on_write` hooks for the writes traced since the last call.
+    pub fn written(&mut self, chip: &mut chip::Chip) -> Result<(), ScriptError> {
+        if self.writes.is_empty() { return Ok(()) }
+        let hooks = {
+            let state = self.state.borrow();
+            self.writes
+                .drain(..)
+                .flat_map(|(addr, byte)| {
+                    state.write[&addr]
+                        .iter()
+                        .map(move |hook| (hook.clone(), vec![byte as INT]))
+                })
+                .collect::<Vec<_>>()
+        };
+        self.call(chip, hooks)
+    }
+
+    /// Call the `on_frame` hooks, after `chip` finishes a frame.
+    pub fn frame(&mut self, chip: &mut chip::Chip) -> Result<(), ScriptError> {
+        let hooks = self.state.borrow().frame.clone();
+        self.call(chip, hooks.into_iter().map(|hook| (hook, Vec::new())))
+    }
+
+    /// Remove and return all pending printed messages.
+    pub fn drain(&mut self) -> std::vec::IntoIter<String> {
+        std::mem::take(&mut self.state.borrow_mut().messages).into_iter()
+    }
+
+    /// Call each hook with its arguments, with `chip`'s state bound for the
+    /// emulator functions, stopping at the first error.
+    fn call<I>(&self, chip: &mut chip::Chip, hooks: I) -> Result<(), ScriptError>
+    where I: IntoIterator<Item = (FnPtr, Vec<INT>)>
+    {
+        {
+            let mut state = self.state.borrow_mut();
+            std::mem::swap(&mut state.machine, &mut chip.machine);
+            state.held = (0..16).filter(|&key| chip.is_held(key)).fold(0, |held, key| held | 1 << key);
+        }
+        let result = hooks
+            .into_iter()
+            .try_for_each(|(hook, args)| hook.call::<Dynamic>(&self.engine, &self.ast, args).map(drop));
+        let mut state = self.state.borrow_mut();
+        std::mem::swap(&mut state.machine, &mut chip.machine);
+        for (key, pressed) in state.keys.drain(..) {
+            if pressed { chip.press(key) } else { chip.release(key) }
+        }
+        result.map_err(ScriptError::from)
+    }
+}
+
+/// Check that script argument `addr` is a memory address.
+fn addr(addr: INT) -> Result<ram::Addr, Box<EvalAltResult>> {
+    match addr {
+    | 0..=0xFFF => Ok(ram::Addr::from(addr as u16)),
+    | _ => Err(format!("Address {:#X} is beyond 0xFFF", addr).into()),
+    }
+}
+
+/// Check that script argument `value` is a register or key number, named by `what`.
+fn nibble(value: INT, what: &str) -> Result<u8, Box<EvalAltResult>> {
+    match value {
+    | 0..=0xF => Ok(value as u8),
+    | _ => Err(format!("{} {:#X} is beyond 0xF", what, value).into()),
+    }
+}
+
+/// Check that script argument `value` fits in a byte.
+fn byte(value: INT) -> Result<u8, Box<EvalAltResult>> {
+    u8::try_from(value).map_err(|_| format!("Value {} does not fit in a byte", value).into())
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        Script::compile("").expect("empty script failed to compile")
+    }
+}
+
+impl trace::Trace for Script {
+    fn write(&mut self, addr: ram::Addr, byte: u8) {
+        if self.state.borrow().write.contains_key(&addr) {
+            self.writes.push((addr, byte));
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use crate::asm;
+use crate::chip;
+use crate::cpu;
+use crate::fault;
+use crate::quirks;
+use crate::ram;
+
+/// Builder for single-instruction tests.
+///
+/// Sets up a `Chip` in an exact state, executes one instruction at the
+/// program counter, and asserts on the resulting state:
+///
+/// ```
+/// use chip::{Asm, ChipTest, Reg};
+///
+/// ChipTest::new()
+///     .regs(&[5, 3])
+///     .exec(Asm::SUB(Reg::from(0), Reg::from(1)))
+///     .expect_regs(&[2, 3])
+///     .expect_vf(1);
+/// ```
+///
+/// Expectations panic with the instruction and the mismatched state.
+pub struct ChipTest {
+    /// Emulator under test
+    chip: chip::Chip,
+
+    /// Instruction executed, if any
+    op: Option<asm::Asm>,
+
+    /// Result of executing it
+    result: Result<(), fault::Fault>,
+}
+
+impl Default for ChipTest {
+    fn default() -> Self {
+        ChipTest::new()
+    }
+}
+
+impl ChipTest {
+    /// Start from a blank emulator with default options.
+    pub fn new() -> Self {
+        ChipTest {
+            chip: chip::Chip::new(std::iter::empty()),
+            op: None,
+            result: Ok(()),
+        }
+    }
+
+    /// Emulate `quirks`.
+    pub fn quirks(mut self, quirks: quirks::Quirks) -> Self {
+        self.chip.options.quirks = quirks;
+        self.chip.machine.options.quirks = quirks;
+        self.chip.machine.display.set_clip(quirks.clip);
+        self
+    }
+
+    /// Handle faults with `policy`.
+    pub fn fault(mut self, policy: fault::Policy) -> Self {
+        self.chip.options.fault = policy;
+        self.chip.machine.options.fault = policy;
+        self
+    }
+
+    /// Set V0, V1, ... to `values`.
+    pub fn regs(mut self, values: &[u8]) -> Self {
+        self.chip.machine.cpu.reg[..values.len()].copy_from_slice(values);
+        self
+    }
+
+    /// Set Vx to `value`.
+    pub fn reg(mut self, x: u8, value: u8) -> Self {
+        self.chip.machine.cpu[cpu::Reg::from(x)] = value;
+        self
+    }
+
+    /// Set I to `addr`.
+    pub fn i(mut self, addr: u16) -> Self {
+        self.chip.machine.cpu.idx = addr.into();
+        self
+    }
+
+    /// Set the program counter to `addr`.
+    pub fn pc(mut self, addr: u16) -> Self {
+        self.chip.machine.cpu.pc = addr.into();
+        self
+    }
+
+    /// Set the delay and sound timers.
+    pub fn timers(mut self, dt: u8, st: u8) -> Self {
+        self.chip.machine.cpu.dt = dt;
+        self.chip.machine.cpu.st = st;
+        self
+    }
+
+    /// Write `bytes` to memory starting at `addr`.
+    pub fn mem(mut self, addr: u16, bytes: &[u8]) -> Self {
+        for (offset, byte) in bytes.iter().enumerate() {
+            self.chip.machine.ram[ram::Addr::from(addr) + offset as u16] = *byte;
+        }
+        self
+    }
+
+    /// Push return addresses onto the stack, from bottom to top.
+    pub fn stack(mut self, frames: &[u16]) -> Self {
+        for frame in frames {
+            let slot = self.chip.machine.cpu.sp.push().expect("stack overflow in test setup");
+            self.chip.machine.stack[slot] = (*frame).into();
+        }
+        self
+    }
+
+    /// Set display pixels at `(x, y)` coordinates.
+    pub fn pixels(mut self, pixels: &[(u8, u8)]) -> Self {
+        for (x, y) in pixels {
+            self.chip.machine.display.draw_sprite(*x, *y, &[0x80]);
+        }
+        self
+    }
+
+    /// Hold down CHIP-8 `key`.
+    pub fn key(mut self, key: u8) -> Self {
+        self.chip.press(key);
+        self
+    }
+
+    /// Write `op` at the program counter and execute it.
+    pub fn exec(mut self, op: asm::Asm) -> Self {
+        let pc = self.chip.machine.cpu.pc;
+        let word = op.encode();
+        self.chip.machine.ram[pc] = (word >> 8) as u8;
+        self.chip.machine.ram[pc + 1] = word as u8;
+        self.op = Some(op);
+        self.result = self.chip.step();
+        self
+    }
+
+    /// Emulator in its current state, for checks not covered here.
+    pub fn chip(&self) -> &chip::Chip {
+        &self.chip
+    }
+
+    fn name(&self) -> String {
+        self.op.map_or_else(|| String::from("setup"), |op| op.to_string())
+    }
+
+    /// Expect V0, V1, ... to equal `values`.
+    pub fn expect_regs(self, values: &[u8]) -> Self {
+        for (x, value) in values.iter().enumerate() {
+            let actual = self.chip.machine.cpu.reg[x];
+            assert!(actual == *value, "{}: V{:X} is {:#04X}, expected {:#04X}", self.name(), x, actual, value);
+        }
+        self
+    }
+
+    /// Expect Vx to equal `value`.
+    pub fn expect_reg(self, x: u8, value: u8) -> Self {
+        let actual = self.chip.machine.cpu[cpu::Reg::from(x)];
+        assert!(actual == value, "{}: V{:X} is {:#04X}, expected {:#04X}", self.name(), x, actual, value);
+        self
+    }
+
+    /// Expect the flag register VF to equal `value`.
+    pub fn expect_vf(self, value: u8) -> Self {
+        self.expect_reg(0xF, value)
+    }
+
+    /// Expect I to equal `addr`.
+    pub fn expect_i(self, addr: u16) -> Self {
+        let actual = self.chip.machine.cpu.idx;
+        assert!(actual == addr.into(), "{}: I is {}, expected {}", self.name(), actual, ram::Addr::from(addr));
+        self
+    }
+
+    /// Expect the program counter to equal `addr`.
+    pub fn expect_pc(self, addr: u16) -> Self {
+        let actual = self.chip.machine.cpu.pc;
+        assert!(actual == addr.into(), "{}: PC is {}, expected {}", self.name(), actual, ram::Addr::from(addr));
+        self
+    }
+
+    /// Expect the delay and sound timers to equal `dt` and `st`.
+    pub fn expect_timers(self, dt: u8, st: u8) -> Self {
+        let actual = (self.chip.machine.cpu.dt, self.chip.machine.cpu.st);
+        assert!(actual == (dt, st), "{}: DT and ST are {:?}, expected {:?}", self.name(), actual, (dt, st));
+        self
+    }
+
+    /// Expect memory starting at `addr` to hold `bytes`.
+    pub fn expect_mem(self, addr: u16, bytes: &[u8]) -> Self {
+        let actual = (0..bytes.len())
+            .map(|offset| self.chip.machine.ram[ram::Addr::from(addr) + offset as u16])
+            .collect::<Vec<_>>();
+        assert!(actual == bytes, "{}: memory at {} is {:02X?}, expected {:02X?}", self.name(), ram::Addr::from(addr), actual, bytes);
+        self
+    }
+
+    /// Expect the stack to hold exactly `frames`, from bottom to top.
+    pub fn expect_stack(self, frames: &[u16]) -> Self {
+        let actual = self.chip.machine.stack.frames(self.chip.machine.cpu.sp).iter().map(|frame| u16::from(*frame)).collect::<Vec<_>>();
+        assert!(actual == frames, "{}: stack is {:03X?}, expected {:03X?}", self.name(), actual, frames);
+        self
+    }
+
+    /// Expect exactly the display pixels at `(x, y)` coordinates to be set.
+    pub fn expect_pixels(self, pixels: &[(u8, u8)]) -> Self {
+        let mut expected = pixels.to_vec();
+        expected.sort_by_key(|&(x, y)| (y, x));
+        let actual = (0..32)
+            .flat_map(|y| (0..64).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.chip.pixel(x, y))
+            .collect::<Vec<_>>();
+        assert!(actual == expected, "{}: pixels {:?} are set, expected {:?}", self.name(), actual, expected);
+        self
+    }
+
+    /// Expect the instruction to have executed without faulting.
+    pub fn expect_ok(self) -> Self {
+        assert!(self.result.is_ok(), "{}: result is {:?}, expected Ok(())", self.name(), self.result);
+        self
+    }
+
+    /// Expect the instruction to have faulted with `fault`.
+    pub fn expect_fault(self, fault: fault::Fault) -> Self {
+        assert!(self.result == Err(fault), "{}: result is {:?}, expected {:?}", self.name(), self.result, Err::<(), _>(fault));
+        self
+    }
+}
@@ -0,0 +1,148 @@
+use crate::asm;
+use crate::ram;
+use crate::trace;
+
+/// Suspicious memory access detected while executing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// Instruction at `pc` overwrote previously executed code at `addr`
+    Overwrite { pc: ram::Addr, addr: ram::Addr },
+
+    /// Executing code at `addr`, which was written at runtime by instruction at `pc`
+    Execute { pc: ram::Addr, addr: ram::Addr },
+
+    /// Instruction at `pc` wrote to interpreter-reserved memory at `addr`
+    Reserved { pc: ram::Addr, addr: ram::Addr },
+
+    /// Instruction at `pc` overwrote font data at `addr`
+    Font { pc: ram::Addr, addr: ram::Addr },
+
+    /// Execution entered interpreter-reserved memory at `addr`
+    Enter { addr: ram::Addr },
+
+    /// Instruction at `pc` called machine code routine at `addr`
+    Sys { pc: ram::Addr, addr: ram::Addr },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+        | Warning::Overwrite { pc, addr } => write!(fmt, "Instruction at {} overwrote executed code at {}", pc, addr),
+        | Warning::Execute { pc, addr } => write!(fmt, "Executing code at {} written at runtime by instruction at {}", addr, pc),
+        | Warning::Reserved { pc, addr } => write!(fmt, "Instruction at {} wrote to interpreter-reserved address {}", pc, addr),
+        | Warning::Font { pc, addr } => write!(fmt, "Instruction at {} overwrote font data at {}", pc, addr),
+        | Warning::Enter { addr } => write!(fmt, "Executing interpreter-reserved address {}", addr),
+        | Warning::Sys { pc, addr } => write!(fmt, "Instruction at {} called unsupported machine code routine at {}", pc, addr),
+        }
+    }
+}
+
+/// Detects suspicious memory accesses.
+///
+/// No checks are enabled by default:
+///
+/// - `Watch::smc` detects self-modifying code. Each warning is raised
+///   once per modification: overwriting code forgets that it was executed,
+///   and executing written bytes forgets that they were written.
+///
+/// - `Watch::strict` treats memory below the program load address as
+///   reserved for the interpreter.
+#[derive(Clone, Debug)]
+pub struct Watch {
+    /// Whether to detect self-modifying code
+    smc: bool,
+
+    /// Font data and program load locations, if memory below the load address is reserved
+    strict: Option<(ram::Addr, ram::Addr)>,
+
+    /// Whether each byte has been executed since it was last written
+    executed: Vec<bool>,
+
+    /// Instruction that last wrote each byte, if not executed since
+    written: Vec<Option<ram::Addr>>,
+
+    /// Address of the instruction currently executing
+    pc: ram::Addr,
+
+    /// Warnings not yet drained
+    warnings: Vec<Warning>,
+}
+
+impl Watch {
+    /// Warn about executing code written at runtime, or overwriting executed code.
+    pub fn smc(mut self) -> Self {
+        self.smc = true;
+        self
+    }
+
+    /// Warn about `SYS` calls, executing interpreter-reserved memory,
+    /// and writes to it or to the font data stored at `font`, where memory below
+    /// the program loaded at `load` is reserved.
+    pub fn strict(mut self, font: ram::Addr, load: ram::Addr) -> Self {
+        self.strict = Some((font, load));
+        self
+    }
+
+    /// Remove and return all pending warnings.
+    pub fn drain(&mut self) -> std::vec::Drain<'_, Warning> {
+        self.warnings.drain(..)
+    }
+}
+
+impl Default for Watch {
+    fn default() -> Self {
+        Watch {
+            smc: false,
+            strict: None,
+            executed: vec![false; 4096],
+            written: vec![None; 4096],
+            pc: ram::Addr::default(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl trace::Trace for Watch {
+    fn exec(&mut self, addr: ram::Addr, op: asm::Asm) {
+        let last = std::mem::replace(&mut self.pc, addr);
+
+        if let Some((_, load)) = self.strict {
+            if addr < load && last >= load {
+                self.warnings.push(Warning::Enter { addr });
+            }
+            if let asm::Asm::SYS(target) = op {
+                self.warnings.push(Warning::Sys { pc: addr, addr: target });
+            }
+        }
+
+        if self.smc {
+            let index = u16::from(addr) as usize;
+            if let Some(pc) = self.written[index].or_else(|| self.written.get(index + 1).cloned().flatten()) {
+                self.warnings.push(Warning::Execute { pc, addr });
+            }
+            for index in index..self.executed.len().min(index + 2) {
+                self.executed[index] = true;
+                self.written[index] = None;
+            }
+        }
+    }
+
+    fn write(&mut self, addr: ram::Addr, _: u8) {
+        if let Some((font, load)) = self.strict {
+            if font <= addr && addr < font + ram::FONT_SIZE {
+                self.warnings.push(Warning::Font { pc: self.pc, addr });
+            } else if addr < load {
+                self.warnings.push(Warning::Reserved { pc: self.pc, addr });
+            }
+        }
+
+        if self.smc {
+            let index = u16::from(addr) as usize;
+            if self.executed[index] {
+                self.warnings.push(Warning::Overwrite { pc: self.pc, addr });
+                self.executed[index] = false;
+            }
+            self.written[index] = Some(self.pc);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Differential and round-trip properties of the instruction decoder,
+//! encoder, disassembler, and assembler.
+
+use chip::{Addr, Asm, Reg};
+use proptest::prelude::*;
+
+/// Reference decoder: instruction word patterns from Cowgod's technical reference,
+/// as (mask, pattern, variant name), in priority order.
+const PATTERNS: [(u16, u16, &str); 35] = [
+    (0xFFFF, 0x00E0, "CLS"),
+    (0xFFFF, 0x00EE, "RET"),
+    (0xF000, 0x0000, "SYS"),
+    (0xF000, 0x1000, "JP"),
+    (0xF000, 0x2000, "CALL"),
+    (0xF000, 0x3000, "SEC"),
+    (0xF000, 0x4000, "SNEC"),
+    (0xF00F, 0x5000, "SER"),
+    (0xF000, 0x6000, "LDC"),
+    (0xF000, 0x7000, "ADDC"),
+    (0xF00F, 0x8000, "LDR"),
+    (0xF00F, 0x8001, "OR"),
+    (0xF00F, 0x8002, "AND"),
+    (0xF00F, 0x8003, "XOR"),
+    (0xF00F, 0x8004, "ADDR"),
+    (0xF00F, 0x8005, "SUB"),
+    (0xF00F, 0x8006, "SHR"),
+    (0xF00F, 0x8007, "SUBN"),
+    (0xF00F, 0x800E, "SHL"),
+    (0xF00F, 0x9000, "SNER"),
+    (0xF000, 0xA000, "LDI"),
+    (0xF000, 0xB000, "JO"),
+    (0xF000, 0xC000, "RND"),
+    (0xF000, 0xD000, "DRW"),
+    (0xF0FF, 0xE09E, "SKP"),
+    (0xF0FF, 0xE0A1, "SKNP"),
+    (0xF0FF, 0xF007, "LDTR"),
+    (0xF0FF, 0xF00A, "LDK"),
+    (0xF0FF, 0xF015, "LDRT"),
+    (0xF0FF, 0xF018, "LDRS"),
+    (0xF0FF, 0xF01E, "ADDI"),
+    (0xF0FF, 0xF029, "LDS"),
+    (0xF0FF, 0xF033, "LDB"),
+    (0xF0FF, 0xF055, "WR"),
+    (0xF0FF, 0xF065, "RD"),
+];
+
+fn reference(word: u16) -> Option<&'static str> {
+    PATTERNS.iter()
+        .find(|(mask, pattern, _)| word & mask == *pattern)
+        .map(|(_, _, name)| *name)
+}
+
+fn decode(word: u16) -> Option<Asm> {
+    Asm::parse((word >> 8) as u8, word as u8)
+}
+
+fn reg() -> impl Strategy<Value = Reg> {
+    (0u8..16).prop_map(Reg::from)
+}
+
+fn addr() -> impl Strategy<Value = Addr> {
+    (0u16..0x1000).prop_map(Addr::from)
+}
+
+fn asm() -> impl Strategy<Value = Asm> {
+    prop_oneof![
+        (0u16..0x1000)
+            .prop_filter("encodes as CLS or RET", |a| *a != 0x0E0 && *a != 0x0EE)
+            .prop_map(|a| Asm::SYS(Addr::from(a))),
+        Just(Asm::CLS),
+        Just(Asm::RET),
+        addr().prop_map(Asm::JP),
+        addr().prop_map(Asm::CALL),
+        (reg(), any::<u8>()).prop_map(|(x, kk)| Asm::SEC(x, kk)),
+        (reg(), any::<u8>()).prop_map(|(x, kk)| Asm::SNEC(x, kk)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::SER(x, y)),
+        (reg(), any::<u8>()).prop_map(|(x, kk)| Asm::LDC(x, kk)),
+        (reg(), any::<u8>()).prop_map(|(x, kk)| Asm::ADDC(x, kk)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::LDR(x, y)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::OR(x, y)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::AND(x, y)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::XOR(x, y)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::ADDR(x, y)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::SUB(x, y)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::SHR(x, y)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::SUBN(x, y)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::SHL(x, y)),
+        (reg(), reg()).prop_map(|(x, y)| Asm::SNER(x, y)),
+        addr().prop_map(Asm::LDI),
+        addr().prop_map(Asm::JO),
+        (reg(), any::<u8>()).prop_map(|(x, kk)| Asm::RND(x, kk)),
+        (reg(), reg(), 0u8..16).prop_map(|(x, y, n)| Asm::DRW(x, y, n)),
+        reg().prop_map(Asm::SKP),
+        reg().prop_map(Asm::SKNP),
+        reg().prop_map(Asm::LDTR),
+        reg().prop_map(Asm::LDK),
+        reg().prop_map(Asm::LDRT),
+        reg().prop_map(Asm::LDRS),
+        reg().prop_map(Asm::ADDI),
+        reg().prop_map(Asm::LDS),
+        reg().prop_map(Asm::LDB),
+        (0u8..16).prop_map(Asm::WR),
+        (0u8..16).prop_map(Asm::RD),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn encode_then_decode(op in asm()) {
+        prop_assert_eq!(decode(op.encode()), Some(op));
+    }
+
+    #[test]
+    fn disassemble_then_assemble(op in asm()) {
+        prop_assert_eq!(op.to_string().parse::<Asm>(), Ok(op));
+    }
+
+    #[test]
+    fn assembler_ignores_case_and_spacing(op in asm()) {
+        let text = op.to_string().to_lowercase().replace(", ", " ,  ");
+        prop_assert_eq!(text.parse::<Asm>(), Ok(op));
+    }
+}
+
+#[test]
+fn every_word() {
+    for word in 0..=0xFFFF {
+        let op = decode(word);
+        assert_eq!(op.map(|op| op.name()), reference(word), "{:#06X}", word);
+        if let Some(op) = op {
+            assert_eq!(op.encode(), word, "{:#06X} decodes to {}", word, op);
+            let text = op.to_string();
+            let assembled = text.parse::<Asm>().unwrap_or_else(|error| panic!("{:#06X}: {}", word, error));
+            assert_eq!(assembled, op, "{:#06X}", word);
+            assert_eq!(assembled.to_string(), text, "{:#06X}", word);
+        }
+    }
+}
+
+#[test]
+fn invalid_text() {
+    for text in &["", "NOP", "LD", "LD V0", "LD VG, 1", "LD V0, 0x100", "JP 0x1000", "DRW V0, V1, 16", "JP V1, 0x200", "CLS V0"] {
+        assert!(text.parse::<Asm>().is_err(), "`{}` assembled", text);
+    }
+}
@@ -0,0 +1,106 @@
+//! Recordings of a ROM that toggles a sprite every frame.
+
+use std::fs;
+use std::path;
+
+use chip::{Chip, Palette, Recorder};
+
+/// 60Hz frames to record.
+const FRAMES: usize = 5;
+
+/// Draw the font sprite for 0 at (0, 0) once per two-instruction frame, toggling it.
+fn chip() -> Chip {
+    Chip::builder().cycles(2).seed(0).build(vec![0xA0, 0x00, 0xD0, 0x05, 0x12, 0x02])
+}
+
+/// Fresh path named `name` in the temporary directory.
+fn temp(name: &str) -> path::PathBuf {
+    let path = std::env::temp_dir().join(format!("chip-{}-{}", std::process::id(), name));
+    fs::remove_dir_all(&path).ok();
+    fs::remove_file(&path).ok();
+    path
+}
+
+/// Record `FRAMES` frames to `path`.
+fn record(path: &path::Path) {
+    let mut chip = chip();
+    let mut recorder = Recorder::create(path, 1, Palette::default()).unwrap();
+    for _ in 0..FRAMES {
+        chip.frame().unwrap();
+        recorder.frame(&chip).unwrap();
+    }
+    recorder.finish().unwrap();
+}
+
+#[test]
+fn gif() {
+    let path = temp("record.gif");
+    record(&path);
+
+    let mut decoder = gif::DecodeOptions::new().read_info(fs::File::open(&path).unwrap()).unwrap();
+    let mut frames = 0;
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        assert_eq!((frame.width, frame.height), (64, 32));
+        frames += 1;
+    }
+    assert_eq!(frames, FRAMES);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn png() {
+    let path = temp("record");
+    record(&path);
+    assert_eq!(fs::read_dir(&path).unwrap().count(), FRAMES);
+    assert!(path.join(format!("{:05}.png", FRAMES - 1)).exists());
+    fs::remove_dir_all(&path).unwrap();
+}
+
+#[test]
+fn screenshot_png() {
+    let path = temp("screenshot.png");
+    let mut chip = chip();
+    chip.frame().unwrap();
+    chip::screenshot(&chip, &path, 2, Palette::default()).unwrap();
+
+    let mut decoder = png::Decoder::new(fs::File::open(&path).unwrap());
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!((info.width, info.height), (128, 64));
+    // The font sprite for 0 starts with a set pixel, and its second row with a gap
+    assert_eq!(pixels[..3], [0xFF, 0xFF, 0xFF]);
+    assert_eq!(pixels[(2 * 128 + 2) * 3..][..3], [0x00, 0x00, 0x00]);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn screenshot_pbm() {
+    let path = temp("screenshot.pbm");
+    let mut chip = chip();
+    chip.frame().unwrap();
+    chip::screenshot(&chip, &path, 1, Palette::default()).unwrap();
+
+    // The font sprite for 0 is white on black, so its set pixels are 0
+    let pbm = fs::read_to_string(&path).unwrap();
+    let lines = pbm.lines().collect::<Vec<_>>();
+    assert_eq!(lines[..2], ["P1", "64 32"]);
+    assert_eq!(lines.len(), 2 + 32);
+    assert!(lines[2].starts_with("0 0 0 0 1 "));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn oversized() {
+    let chip = chip();
+    for name in &["oversized.gif", "oversized", "oversized.png"] {
+        let path = temp(name);
+        let error = match *name {
+        | "oversized.png" => chip::screenshot(&chip, &path, chip::MAX_SCALE + 1, Palette::default()).unwrap_err(),
+        | _ => Recorder::create(&path, chip::MAX_SCALE + 1, Palette::default()).err().unwrap(),
+        };
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(!path.exists());
+    }
+}
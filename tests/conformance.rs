@@ -0,0 +1,243 @@
+//! Runs hand-assembled stand-ins for the opcode, flags, quirks, and keypad
+//! test ROMs headlessly under each quirks preset, and compares the final
+//! display against golden framebuffers in `tests/golden`.
+//!
+//! These only cover the checks written here, not the published test ROMs,
+//! which are checked by hand as described in the README.
+//!
+//! Set `CHIP_BLESS=1` to write the current output as the new golden files.
+
+use std::path;
+
+use chip::{Chip, Options, Quirks};
+
+/// 60Hz frames to run each ROM for.
+const FRAMES: u32 = 60;
+
+/// Instructions per frame.
+const STEPS: u32 = 10;
+
+/// Hand-assembled test program.
+///
+/// Results are drawn as hex digits left to right, one line of digits per group
+/// of checks. `show` uses VB through VE, so checks stick to V0 through VA and VF.
+struct Rom(Vec<u16>);
+
+impl Rom {
+    fn new() -> Self {
+        Rom(vec![0x6C00, 0x6D00])
+    }
+
+    fn op(&mut self, ops: &[u16]) -> &mut Self {
+        self.0.extend_from_slice(ops);
+        self
+    }
+
+    /// Address of the next instruction.
+    fn here(&self) -> u16 {
+        0x200 + 2 * self.0.len() as u16
+    }
+
+    /// Draw the low nibble of Vx as a hex digit.
+    fn show(&mut self, x: u16) -> &mut Self {
+        self.op(&[0x8E00 | x << 4, 0x6B0F, 0x8EB2, 0xFE29, 0xDCD5, 0x7C05])
+    }
+
+    /// Copy VF into VA, then draw Vx and VA.
+    fn flag(&mut self, x: u16) -> &mut Self {
+        self.op(&[0x8AF0]).show(x).show(0xA)
+    }
+
+    /// Start a new line of digits.
+    fn line(&mut self) -> &mut Self {
+        self.op(&[0x6C00, 0x7D06])
+    }
+
+    /// Loop forever.
+    fn finish(&mut self) -> Vec<u8> {
+        let here = self.here();
+        self.op(&[0x1000 | here]);
+        self.0.iter().flat_map(|op| op.to_be_bytes().to_vec()).collect()
+    }
+}
+
+/// Skips, loads, ALU, subroutines, memory, and timers.
+fn opcodes() -> Vec<u8> {
+    let mut rom = Rom::new();
+
+    // Subroutine at 0x206 setting V4 to 9
+    rom.op(&[0x120A, 0x6409, 0x00EE]);
+
+    rom.op(&[0x6100])
+        .op(&[0x3100, 0x7101]).show(1)
+        .op(&[0x4101, 0x7101]).show(1)
+        .op(&[0x6200, 0x5120, 0x7101]).show(1)
+        .op(&[0x6305, 0x9130, 0x7101]).show(1)
+        .op(&[0x3105, 0x7101]).show(1)
+        .op(&[0x4101, 0x7101]).show(1)
+        .op(&[0x5130, 0x7101]).show(1)
+        .op(&[0x9120, 0x7101]).show(1)
+        .line();
+
+    rom.op(&[0x60FF, 0x7002]).show(0)
+        .op(&[0x6107, 0x8010]).show(0)
+        .op(&[0x6003, 0x6104, 0x8011]).show(0)
+        .op(&[0x6006, 0x6103, 0x8012]).show(0)
+        .op(&[0x6006, 0x6103, 0x8013]).show(0)
+        .op(&[0x6400, 0x2206]).show(4);
+    let skip = rom.here() + 4;
+    rom.op(&[0x1000 | skip, 0x7401]).show(4)
+        .line();
+
+    rom.op(&[0xA400, 0x6001, 0x6102, 0x6203, 0xF255])
+        .op(&[0x6000, 0x6100, 0x6200, 0xA400, 0xF265]).show(0).show(1).show(2)
+        .op(&[0xA400, 0x6002, 0xF01E, 0xF065]).show(0)
+        .op(&[0x60FE, 0xA410, 0xF033, 0xA410, 0xF265]).show(0).show(1).show(2)
+        .op(&[0x6005, 0xF015, 0xF107]).show(1);
+
+    rom.finish()
+}
+
+/// Results and VF for `8xy4` through `8xyE`, including VF as an operand.
+fn flags() -> Vec<u8> {
+    let mut rom = Rom::new();
+
+    rom.op(&[0x60F0, 0x6120, 0x8014]).flag(0)
+        .op(&[0x6003, 0x6104, 0x8014]).flag(0)
+        .op(&[0x6F05, 0x6102, 0x8F14]).flag(0xF)
+        .op(&[0x6FFF, 0x6102, 0x8F14]).flag(0xF)
+        .line();
+
+    rom.op(&[0x6005, 0x6103, 0x8015]).flag(0)
+        .op(&[0x6003, 0x6105, 0x8015]).flag(0)
+        .op(&[0x6005, 0x6105, 0x8015]).flag(0)
+        .op(&[0x6F05, 0x6102, 0x8F15]).flag(0xF)
+        .line();
+
+    rom.op(&[0x6003, 0x6105, 0x8017]).flag(0)
+        .op(&[0x6005, 0x6103, 0x8017]).flag(0)
+        .op(&[0x6F02, 0x6105, 0x8F17]).flag(0xF)
+        .line();
+
+    rom.op(&[0x6006, 0x6103, 0x8016]).flag(0)
+        .op(&[0x6081, 0x6103, 0x801E]).flag(0)
+        .op(&[0x6F02, 0x8FF6]).flag(0xF)
+        .line();
+
+    rom.op(&[0x6F07, 0x6003, 0x6104, 0x8011]).flag(0)
+        .op(&[0x6F07, 0x8012]).flag(0)
+        .op(&[0x6F07, 0x8013]).flag(0);
+
+    rom.finish()
+}
+
+/// One digit per quirk, then a sprite drawn across the bottom right corner.
+fn quirks() -> Vec<u8> {
+    let mut rom = Rom::new();
+
+    // Leave the top line for the wrapped sprite
+    rom.line();
+
+    // VF reset: 0 if reset
+    rom.op(&[0x6F07, 0x8011]).flag(0xF);
+
+    // Shift: 3 in place, 1 from Vy
+    rom.op(&[0x6006, 0x6103, 0x8016]).show(0);
+
+    // Memory: 0 if I advanced, 1 if not
+    rom.op(&[0xA400, 0x6001, 0x6102, 0xF155, 0xF065]).show(0);
+
+    // Jump: 1 if offset by V2, 2 if offset by V0
+    let target = rom.here() + 6;
+    assert_eq!(target >> 8, 2);
+    rom.op(&[0x6004, 0x6200, 0xB000 | target])
+        .op(&[0x6101, 0x1000 | (target + 8), 0x6102, 0x1000 | (target + 8)])
+        .show(1);
+
+    // Clip: four rows of eight pixels at (60, 30)
+    rom.op(&[0x60FF, 0x61FF, 0x62FF, 0x63FF, 0xA410, 0xF355, 0xA410, 0x643C, 0x651E, 0xD454]);
+
+    rom.finish()
+}
+
+/// Waits for a key, then tests it with `SKP` and `SKNP` while it is held.
+fn keypad() -> Vec<u8> {
+    let mut rom = Rom::new();
+
+    rom.op(&[0xF00A]).show(0)
+        .op(&[0x6100, 0xE09E, 0x7101]).show(1)
+        .op(&[0x6205, 0xE2A1, 0x7101]).show(1)
+        .op(&[0xE0A1, 0x7101]).show(1);
+
+    rom.finish()
+}
+
+/// Run `rom` under `quirks`, calling `input` before each frame.
+fn run(rom: &[u8], quirks: Quirks, mut input: impl FnMut(u32, &mut Chip)) -> Chip {
+    let options = Options { quirks, ..Options::default() };
+    let mut chip = Chip::with_options(rom.iter().cloned(), options);
+    for frame in 0..FRAMES {
+        input(frame, &mut chip);
+        for _ in 0..STEPS {
+            chip.step().expect("test ROM faulted");
+        }
+        chip.tick();
+    }
+    chip
+}
+
+/// Display as text, with `#` for set pixels and `.` for unset ones.
+fn framebuffer(chip: &Chip) -> String {
+    let mut text = String::new();
+    for y in 0..32 {
+        for x in 0..64 {
+            text.push(if chip.pixel(x, y) { '#' } else { '.' });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn golden() -> path::PathBuf {
+    path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+/// Compare `actual` against the golden file at `path`, or overwrite it if blessing.
+fn check(path: &path::Path, actual: &str) {
+    if std::env::var_os("CHIP_BLESS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, actual).unwrap();
+        return;
+    }
+    match std::fs::read_to_string(path) {
+    | Ok(expected) => assert!(expected == actual, "{} differs from output:\n{}", path.display(), actual),
+    | Err(error) => panic!("{}: {} (run with CHIP_BLESS=1 to create it)", path.display(), error),
+    }
+}
+
+fn conform(name: &str, rom: &[u8], mut input: impl FnMut(u32, &mut Chip)) {
+    for preset in Quirks::PRESETS.iter() {
+        let chip = run(rom, Quirks::preset(preset).unwrap(), &mut input);
+        check(&golden().join(format!("{}-{}.txt", name, preset)), &framebuffer(&chip));
+    }
+}
+
+#[test]
+fn opcode_test() {
+    conform("opcodes", &opcodes(), |_, _| ());
+}
+
+#[test]
+fn flags_test() {
+    conform("flags", &flags(), |_, _| ());
+}
+
+#[test]
+fn quirks_test() {
+    conform("quirks", &quirks(), |_, _| ());
+}
+
+#[test]
+fn keypad_test() {
+    conform("keypad", &keypad(), |frame, chip| if frame == 2 { chip.press(0xA) });
+}
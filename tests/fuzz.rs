@@ -0,0 +1,40 @@
+//! Randomized runner for the same inputs as the cargo-fuzz targets, on stable Rust.
+//!
+//! Run more cases with e.g. `PROPTEST_CASES=100000 cargo test --release --test fuzz`.
+
+use chip::fuzz::Input;
+use chip::Asm;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// 60Hz frames to run each input for.
+const FRAMES: u32 = 30;
+
+/// Instruction word that decodes, replacing invalid ones with `SYS`.
+fn valid() -> impl Strategy<Value = [u8; 2]> {
+    any::<[u8; 2]>().prop_map(|[hi, lo]| if Asm::parse(hi, lo).is_some() { [hi, lo] } else { [hi & 0x0F, lo] })
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_bytes(data in vec(any::<u8>(), 0..4096)) {
+        Input::decode(&data).run(FRAMES);
+    }
+
+    #[test]
+    fn valid_instructions(header in vec(any::<u8>(), 2..32), rom in vec(valid(), 0..1024)) {
+        let data = header.into_iter().chain(rom.into_iter().flatten()).collect::<Vec<_>>();
+        Input::decode(&data).run(FRAMES);
+    }
+}
+
+#[test]
+fn invalid_instruction() {
+    let input = Input::decode(&[0, 0, 0x50, 0x01]);
+    assert_eq!(input.run(FRAMES), Some(chip::Fault::Opcode { pc: chip::Addr::from(0x200), op: 0x5001 }));
+}
+
+#[test]
+fn seeded() {
+    assert!(Input::decode(&[]).options.seed.is_some());
+}
@@ -0,0 +1,96 @@
+//! Rhai script hooks driving hand-assembled ROMs.
+
+use chip::{Chip, Script};
+
+/// Run `frames` 60Hz frames of `rom` with `script`, calling hooks like the emulator does.
+fn run(rom: &[u8], script: &str, frames: u32) -> (Chip, Script) {
+    let mut chip = Chip::builder().seed(0).build(rom.to_vec());
+    let mut script = Script::compile(script).unwrap();
+    for _ in 0..frames {
+        for _ in 0..chip.options().cycles {
+            script.reached(&mut chip).unwrap();
+            chip.step_traced(&mut script).unwrap();
+            script.written(&mut chip).unwrap();
+        }
+        chip.tick();
+        script.frame(&mut chip).unwrap();
+    }
+    (chip, script)
+}
+
+#[test]
+fn frame() {
+    // Count up V0 forever
+    let rom = [0x70, 0x01, 0x12, 0x00];
+    let (_, mut script) = run(&rom, "let n = 0; on_frame(|| { n += 1; print(`${n}: ${reg(0)}`) });", 2);
+    assert_eq!(script.drain().collect::<Vec<_>>(), ["1: 5", "2: 10"]);
+}
+
+#[test]
+fn pc() {
+    // Set V1 to 1 at 0x202, which the hook skips over, then store V0 at 0x300
+    let rom = [0x60, 0x07, 0x61, 0x01, 0xA3, 0x00, 0xF0, 0x55, 0x12, 0x08];
+    let script = r#"
+        on_pc(0x202, || set_pc(pc() + 2));
+        on_pc(0x206, || set_reg(0, reg(0) * 2));
+    "#;
+    let (chip, _) = run(&rom, script, 1);
+    assert_eq!(chip.cpu().reg[1], 0);
+    assert_eq!(chip.mem()[0x300.into()], 14);
+}
+
+#[test]
+fn write() {
+    // Store V0 at 0x300, then overwrite V0 by loading it back
+    let rom = [0x60, 0x09, 0xA3, 0x00, 0xF0, 0x55, 0xA3, 0x00, 0xF0, 0x65, 0x12, 0x0A];
+    let script = r#"
+        on_write(0x300, |byte| { print(`wrote ${byte}`); poke(0x300, 3) });
+        on_write(0x301, |byte| print("unreachable"));
+    "#;
+    let (chip, mut script) = run(&rom, script, 1);
+    assert_eq!(chip.cpu().reg[0], 3);
+    assert_eq!(script.drain().collect::<Vec<_>>(), ["wrote 9"]);
+}
+
+#[test]
+fn keypad() {
+    // Loop until key 5 is held, then set V1
+    let rom = [0x60, 0x05, 0xE0, 0x9E, 0x12, 0x02, 0x61, 0x01, 0x12, 0x08];
+    let script = "on_frame(|| if held(5) { release(5) } else if peek(0x206) == 0x61 { press(5) });";
+    let (mut chip, _) = run(&rom, script, 1);
+    assert!(chip.is_held(5));
+    assert_eq!(chip.cpu().reg[1], 0);
+
+    chip.frame().unwrap();
+    assert_eq!(chip.cpu().reg[1], 1);
+}
+
+#[test]
+fn error() {
+    assert!(Script::compile("on_frame(|| ").is_err());
+    assert!(Script::compile("poke(0x300)").err().unwrap().to_string().contains("poke"));
+
+    let mut chip = Chip::new(vec![0x12, 0x00]);
+    let mut script = Script::compile(r#"on_frame(|| throw "game over");"#).unwrap();
+    assert!(script.frame(&mut chip).unwrap_err().to_string().contains("game over"));
+}
+
+#[test]
+fn range() {
+    assert!(Script::compile("on_pc(0x1000, || ())").err().unwrap().to_string().contains("0x1000"));
+    assert!(Script::compile("on_write(-1, |byte| ())").is_err());
+
+    let mut chip = Chip::new(vec![0x12, 0x00]);
+    for hook in &["reg(16)", "set_reg(0, 256)", "set_i(0x1000)", "peek(0x1000)", "held(16)", "press(-1)"] {
+        let mut script = Script::compile(&format!("on_frame(|| {});", hook)).unwrap();
+        assert!(script.frame(&mut chip).is_err(), "{}", hook);
+    }
+    assert!(!chip.is_held(15));
+}
+
+#[test]
+fn runaway() {
+    let mut chip = Chip::new(vec![0x12, 0x00]);
+    let mut script = Script::compile("on_frame(|| loop {});").unwrap();
+    assert!(script.frame(&mut chip).is_err());
+}
@@ -0,0 +1,274 @@
+//! Semantics of each instruction, under every quirks preset.
+
+use chip::{Addr, Asm, Chip, ChipTest, Fault, Overlap, Policy, Quirks, Reg};
+
+fn v(x: u8) -> Reg {
+    Reg::from(x)
+}
+
+/// Run `test` with a fresh `ChipTest` for each quirks preset.
+fn each(test: impl Fn(Quirks, ChipTest)) {
+    for preset in Quirks::PRESETS.iter() {
+        let quirks = Quirks::preset(preset).unwrap();
+        test(quirks, ChipTest::new().quirks(quirks));
+    }
+}
+
+#[test]
+fn registers() {
+    assert_eq!(v(0x10), v(0x0));
+    assert_eq!(v(0xFF), v(0xF));
+    assert_eq!(v(0xF) + 1, v(0x0));
+    assert_eq!(v(0x1A).to_string(), "VA");
+}
+
+#[test]
+fn sys() {
+    each(|_, t| { t.exec(Asm::SYS(Addr::from(0x123))).expect_pc(0x202); });
+}
+
+#[test]
+fn cls() {
+    each(|_, t| { t.pixels(&[(0, 0), (63, 31)]).exec(Asm::CLS).expect_pixels(&[]); });
+}
+
+#[test]
+fn ret() {
+    each(|_, t| { t.stack(&[0x300, 0x400]).exec(Asm::RET).expect_pc(0x400).expect_stack(&[0x300]); });
+    each(|_, t| { t.exec(Asm::RET).expect_fault(Fault::StackUnderflow { pc: Addr::from(0x200) }).expect_pc(0x200); });
+}
+
+#[test]
+fn jp() {
+    each(|_, t| { t.exec(Asm::JP(Addr::from(0x345))).expect_pc(0x345); });
+}
+
+#[test]
+fn call() {
+    each(|_, t| { t.exec(Asm::CALL(Addr::from(0x345))).expect_pc(0x345).expect_stack(&[0x202]); });
+    each(|_, t| {
+        t.stack(&[0x300; 16])
+            .exec(Asm::CALL(Addr::from(0x345)))
+            .expect_fault(Fault::StackOverflow { pc: Addr::from(0x200) })
+            .expect_pc(0x200);
+    });
+}
+
+#[test]
+fn wrapping_call() {
+    // The 17th frame overwrites the bottom of the stack
+    let frames = (0..16).map(|frame| 0x300 + 2 * frame).collect::<Vec<_>>();
+    each(|_, t| {
+        t.fault(Policy::Wrap)
+            .stack(&frames)
+            .exec(Asm::CALL(Addr::from(0x345)))
+            .expect_ok()
+            .expect_pc(0x345)
+            .expect_stack(&[0x202]);
+    });
+}
+
+#[test]
+fn wrapping_ret() {
+    // Returning from an empty stack pops the top slot
+    let frames = (0..16).map(|frame| 0x300 + 2 * frame).collect::<Vec<_>>();
+    each(|_, t| {
+        let t = t.fault(Policy::Wrap)
+            .stack(&frames)
+            .exec(Asm::CALL(Addr::from(0x345)))
+            .exec(Asm::RET)
+            .expect_pc(0x202)
+            .expect_stack(&[]);
+        let mut expected = frames[..15].to_vec();
+        expected[0] = 0x202;
+        t.exec(Asm::RET).expect_ok().expect_pc(0x31E).expect_stack(&expected);
+    });
+}
+
+#[test]
+fn skips() {
+    each(|_, t| { t.regs(&[7]).exec(Asm::SEC(v(0), 7)).expect_pc(0x204); });
+    each(|_, t| { t.regs(&[7]).exec(Asm::SEC(v(0), 8)).expect_pc(0x202); });
+    each(|_, t| { t.regs(&[7]).exec(Asm::SNEC(v(0), 8)).expect_pc(0x204); });
+    each(|_, t| { t.regs(&[7]).exec(Asm::SNEC(v(0), 7)).expect_pc(0x202); });
+    each(|_, t| { t.regs(&[7, 7]).exec(Asm::SER(v(0), v(1))).expect_pc(0x204); });
+    each(|_, t| { t.regs(&[7, 8]).exec(Asm::SER(v(0), v(1))).expect_pc(0x202); });
+    each(|_, t| { t.regs(&[7, 8]).exec(Asm::SNER(v(0), v(1))).expect_pc(0x204); });
+    each(|_, t| { t.regs(&[7, 7]).exec(Asm::SNER(v(0), v(1))).expect_pc(0x202); });
+}
+
+#[test]
+fn loads() {
+    each(|_, t| { t.exec(Asm::LDC(v(3), 0xAB)).expect_reg(3, 0xAB); });
+    each(|_, t| { t.regs(&[1, 2]).exec(Asm::LDR(v(0), v(1))).expect_regs(&[2, 2]); });
+    each(|_, t| { t.exec(Asm::LDI(Addr::from(0x456))).expect_i(0x456); });
+}
+
+#[test]
+fn addc() {
+    each(|_, t| { t.regs(&[0xFF]).reg(0xF, 5).exec(Asm::ADDC(v(0), 2)).expect_regs(&[1]).expect_vf(5); });
+}
+
+#[test]
+fn logic() {
+    each(|quirks, t| {
+        let vf = if quirks.vf_reset { 0 } else { 9 };
+        t.regs(&[0b1100, 0b1010]).reg(0xF, 9).exec(Asm::OR(v(0), v(1))).expect_regs(&[0b1110, 0b1010]).expect_vf(vf);
+    });
+    each(|quirks, t| {
+        let vf = if quirks.vf_reset { 0 } else { 9 };
+        t.regs(&[0b1100, 0b1010]).reg(0xF, 9).exec(Asm::AND(v(0), v(1))).expect_regs(&[0b1000, 0b1010]).expect_vf(vf);
+    });
+    each(|quirks, t| {
+        let vf = if quirks.vf_reset { 0 } else { 9 };
+        t.regs(&[0b1100, 0b1010]).reg(0xF, 9).exec(Asm::XOR(v(0), v(1))).expect_regs(&[0b0110, 0b1010]).expect_vf(vf);
+    });
+}
+
+#[test]
+fn addr() {
+    each(|_, t| { t.regs(&[0xF0, 0x20]).exec(Asm::ADDR(v(0), v(1))).expect_regs(&[0x10]).expect_vf(1); });
+    each(|_, t| { t.regs(&[3, 4]).exec(Asm::ADDR(v(0), v(1))).expect_regs(&[7]).expect_vf(0); });
+    each(|_, t| { t.regs(&[0, 2]).reg(0xF, 0xFF).exec(Asm::ADDR(v(0xF), v(1))).expect_vf(1); });
+}
+
+#[test]
+fn sub() {
+    each(|_, t| { t.regs(&[5, 3]).exec(Asm::SUB(v(0), v(1))).expect_regs(&[2]).expect_vf(1); });
+    each(|_, t| { t.regs(&[3, 5]).exec(Asm::SUB(v(0), v(1))).expect_regs(&[0xFE]).expect_vf(0); });
+    each(|_, t| { t.regs(&[5, 5]).exec(Asm::SUB(v(0), v(1))).expect_regs(&[0]).expect_vf(1); });
+    each(|_, t| { t.regs(&[0, 2]).reg(0xF, 5).exec(Asm::SUB(v(0xF), v(1))).expect_vf(1); });
+}
+
+#[test]
+fn subn() {
+    each(|_, t| { t.regs(&[3, 5]).exec(Asm::SUBN(v(0), v(1))).expect_regs(&[2]).expect_vf(1); });
+    each(|_, t| { t.regs(&[5, 3]).exec(Asm::SUBN(v(0), v(1))).expect_regs(&[0xFE]).expect_vf(0); });
+    each(|_, t| { t.regs(&[0, 5]).reg(0xF, 2).exec(Asm::SUBN(v(0xF), v(1))).expect_vf(1); });
+}
+
+#[test]
+fn shifts() {
+    each(|quirks, t| {
+        let (vx, vf) = if quirks.shift { (0b011, 0) } else { (0b001, 1) };
+        t.regs(&[0b110, 0b011]).exec(Asm::SHR(v(0), v(1))).expect_regs(&[vx, 0b011]).expect_vf(vf);
+    });
+    each(|quirks, t| {
+        let (vx, vf) = if quirks.shift { (0x02, 1) } else { (0x06, 0) };
+        t.regs(&[0x81, 0x03]).exec(Asm::SHL(v(0), v(1))).expect_regs(&[vx, 0x03]).expect_vf(vf);
+    });
+    each(|_, t| { t.reg(0xF, 0x03).exec(Asm::SHR(v(0xF), v(0xF))).expect_vf(1); });
+    each(|_, t| { t.reg(0xF, 0x40).exec(Asm::SHL(v(0xF), v(0xF))).expect_vf(0); });
+}
+
+#[test]
+fn jo() {
+    each(|quirks, t| {
+        let pc = if quirks.jump { 0x348 } else { 0x344 };
+        t.regs(&[0x04, 0x00, 0x00, 0x08]).exec(Asm::JO(Addr::from(0x340))).expect_pc(pc);
+    });
+}
+
+#[test]
+fn rnd() {
+    each(|_, t| { t.regs(&[0xFF]).exec(Asm::RND(v(0), 0x00)).expect_regs(&[0]).expect_pc(0x202); });
+}
+
+#[test]
+fn drw() {
+    // Font digit 0 at address 0: 0xF0, 0x90, 0x90, 0x90, 0xF0
+    let zero = [
+        (0, 0), (1, 0), (2, 0), (3, 0),
+        (0, 1), (3, 1),
+        (0, 2), (3, 2),
+        (0, 3), (3, 3),
+        (0, 4), (1, 4), (2, 4), (3, 4),
+    ];
+    each(|_, t| { t.i(0x000).exec(Asm::DRW(v(0), v(1), 5)).expect_pixels(&zero).expect_vf(0); });
+    each(|_, t| { t.i(0x000).pixels(&[(0, 0), (1, 1)]).exec(Asm::DRW(v(0), v(1), 1)).expect_pixels(&[(1, 0), (2, 0), (3, 0), (1, 1)]).expect_vf(1); });
+    each(|quirks, t| {
+        let pixels: &[(u8, u8)] = if quirks.clip {
+            &[(62, 31), (63, 31)]
+        } else {
+            &[(0, 0), (1, 0), (62, 0), (63, 0), (0, 31), (1, 31), (62, 31), (63, 31)]
+        };
+        t.regs(&[62, 31]).mem(0x300, &[0xF0, 0xF0]).i(0x300).exec(Asm::DRW(v(0), v(1), 2)).expect_pixels(pixels);
+    });
+    each(|_, t| {
+        t.i(0xFFE).exec(Asm::DRW(v(0), v(1), 5))
+            .expect_fault(Fault::Memory { pc: Addr::from(0x200), idx: Addr::from(0xFFE), offset: 4 })
+            .expect_pixels(&[]);
+    });
+}
+
+#[test]
+fn keys() {
+    each(|_, t| { t.key(0xA).regs(&[0xA]).exec(Asm::SKP(v(0))).expect_pc(0x204); });
+    each(|_, t| { t.regs(&[0xA]).exec(Asm::SKP(v(0))).expect_pc(0x202); });
+    each(|_, t| { t.regs(&[0xA]).exec(Asm::SKNP(v(0))).expect_pc(0x204); });
+    each(|_, t| { t.key(0xA).regs(&[0xA]).exec(Asm::SKNP(v(0))).expect_pc(0x202); });
+    each(|_, t| { t.exec(Asm::LDK(v(3))).expect_pc(0x200); });
+    each(|_, t| { t.key(0x7).exec(Asm::LDK(v(3))).expect_pc(0x202).expect_reg(3, 0x7); });
+}
+
+#[test]
+fn timers() {
+    each(|_, t| { t.timers(42, 0).exec(Asm::LDTR(v(2))).expect_reg(2, 42); });
+    each(|_, t| { t.regs(&[9]).exec(Asm::LDRT(v(0))).expect_timers(9, 0); });
+    each(|_, t| { t.regs(&[9]).exec(Asm::LDRS(v(0))).expect_timers(0, 9); });
+}
+
+#[test]
+fn index() {
+    each(|_, t| { t.regs(&[0x10]).i(0x300).exec(Asm::ADDI(v(0))).expect_i(0x310); });
+    each(|_, t| { t.regs(&[0xA]).exec(Asm::LDS(v(0))).expect_i(0x032); });
+    each(|_, t| { t.regs(&[254]).i(0x300).exec(Asm::LDB(v(0))).expect_mem(0x300, &[2, 5, 4]).expect_i(0x300); });
+}
+
+#[test]
+fn font_overlap() {
+    let build = |font: u16, rom: &[u8]| Chip::builder().font(font.into()).try_build(rom.iter().cloned()).err();
+    let overlap = |font: u16| Some(Overlap { font: Addr::from(font), load: Addr::from(0x200) });
+    assert_eq!(build(0x1B0, &[0x00, 0xE0]), None);
+    assert_eq!(build(0x1B1, &[]), None);
+    assert_eq!(build(0x1B1, &[0x00]), overlap(0x1B1));
+    assert_eq!(build(0x202, &[0x00, 0xE0]), None);
+    assert_eq!(build(0x201, &[0x00, 0xE0]), overlap(0x201));
+}
+
+#[test]
+fn wr() {
+    each(|quirks, t| {
+        let i = if quirks.memory { 0x303 } else { 0x300 };
+        t.regs(&[1, 2, 3, 4]).i(0x300).exec(Asm::WR(2)).expect_mem(0x300, &[1, 2, 3, 0]).expect_i(i);
+    });
+    each(|_, t| {
+        t.i(0xFFE).exec(Asm::WR(2))
+            .expect_fault(Fault::Memory { pc: Addr::from(0x200), idx: Addr::from(0xFFE), offset: 2 })
+            .expect_mem(0xFFE, &[0, 0]);
+    });
+}
+
+#[test]
+fn wrapping_wr() {
+    // Writes past 0xFFF wrap around to 0x000
+    each(|quirks, t| {
+        let i = if quirks.memory { 0x001 } else { 0xFFE };
+        t.fault(Policy::Wrap)
+            .regs(&[1, 2, 3])
+            .i(0xFFE)
+            .exec(Asm::WR(2))
+            .expect_ok()
+            .expect_mem(0xFFE, &[1, 2])
+            .expect_mem(0x000, &[3])
+            .expect_i(i);
+    });
+}
+
+#[test]
+fn rd() {
+    each(|quirks, t| {
+        let i = if quirks.memory { 0x303 } else { 0x300 };
+        t.mem(0x300, &[1, 2, 3, 4]).i(0x300).exec(Asm::RD(2)).expect_regs(&[1, 2, 3, 0]).expect_i(i);
+    });
+}
@@ -0,0 +1,93 @@
+//! WebAssembly bindings for running CHIP-8 programs in the browser or Node.
+//!
+//! Build with `wasm-pack build wasm`, then from JavaScript:
+//!
+//! ```js
+//! import { Chip } from "chip-wasm";
+//!
+//! const chip = new Chip(Math.random() * 2 ** 32);
+//! chip.load_rom(new Uint8Array(await (await fetch("PONG")).arrayBuffer()));
+//! document.onkeydown = event => chip.key_down(0x1);
+//! document.onkeyup = event => chip.key_up(0x1);
+//!
+//! function frame() {
+//!     chip.run_frame();
+//!     const pixels = chip.framebuffer(); // 64 * 32 bytes, 1 if set
+//!     const beep = chip.sound_active();
+//!     requestAnimationFrame(frame);
+//! }
+//! ```
+
+use chip_core::input;
+use chip_core::machine;
+use wasm_bindgen::prelude::*;
+
+/// Instructions per 60Hz frame by default.
+const CYCLES: u32 = 10;
+
+/// CHIP-8 emulator for JavaScript.
+#[wasm_bindgen]
+pub struct Chip {
+    /// Interpreter state
+    machine: machine::Machine,
+
+    /// Keypad and random number input
+    input: input::Input,
+
+    /// Instructions per 60Hz frame
+    cycles: u32,
+}
+
+#[wasm_bindgen]
+impl Chip {
+    /// Create an emulator with no program loaded, seeding `RND` with `seed`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u32) -> Self {
+        Chip {
+            machine: machine::Machine::new(core::iter::empty(), machine::Options::default()),
+            input: input::Input::new(seed),
+            cycles: CYCLES,
+        }
+    }
+
+    /// Reset the emulator and load `rom` at 0x200.
+    pub fn load_rom(&mut self, rom: &[u8]) {
+        let options = self.machine.options;
+        self.machine = machine::Machine::new(rom.iter().cloned(), options);
+        self.input.forget();
+    }
+
+    /// Execute `cycles` instructions per frame, 10 by default.
+    pub fn set_cycles(&mut self, cycles: u32) {
+        self.cycles = cycles;
+    }
+
+    /// Execute one 60Hz frame, throwing the fault message if an instruction faults.
+    pub fn run_frame(&mut self) -> Result<(), JsError> {
+        for _ in 0..self.cycles {
+            self.machine.step(&mut self.input, &mut ()).map_err(|fault| JsError::new(&fault.to_string()))?;
+        }
+        self.machine.tick();
+        Ok(())
+    }
+
+    /// Hold down CHIP-8 `key`, from 0x0 to 0xF.
+    pub fn key_down(&mut self, key: u8) {
+        self.input.press(key);
+    }
+
+    /// Release CHIP-8 `key`.
+    pub fn key_up(&mut self, key: u8) {
+        self.input.release(key);
+    }
+
+    /// Pixels row by row from the top left, one byte each: 1 if set, 0 if not.
+    pub fn framebuffer(&self) -> Vec<u8> {
+        self.machine.display.bytes().to_vec()
+    }
+
+    /// Whether the sound timer is running, so a tone should be playing.
+    pub fn sound_active(&self) -> bool {
+        self.machine.cpu.st > 0
+    }
+}
@@ -0,0 +1,62 @@
+//! Run in Node with `wasm-pack test --node wasm`.
+
+use chip_wasm::Chip;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn framebuffer() {
+    let mut chip = Chip::new(1);
+    assert_eq!(chip.framebuffer(), vec![0; 64 * 32]);
+
+    // Draw the font sprite for 0 at (0, 0)
+    chip.load_rom(&[0xA0, 0x00, 0xD0, 0x05, 0x12, 0x04]);
+    chip.run_frame().unwrap();
+    let pixels = chip.framebuffer();
+    assert_eq!(&pixels[..4], &[1, 1, 1, 1]);
+    assert_eq!(&pixels[64..68], &[1, 0, 0, 1]);
+    assert_eq!(pixels.iter().filter(|&&pixel| pixel == 1).count(), 14);
+}
+
+#[wasm_bindgen_test]
+fn wait_for_key() {
+    // Wait for a key, then draw its font sprite at (0, 0)
+    let mut chip = Chip::new(1);
+    chip.load_rom(&[0xF0, 0x0A, 0xF0, 0x29, 0x61, 0x00, 0xD1, 0x15, 0x12, 0x08]);
+    chip.run_frame().unwrap();
+    assert_eq!(chip.framebuffer(), vec![0; 64 * 32]);
+
+    chip.key_down(0x7);
+    chip.key_up(0x7);
+    chip.run_frame().unwrap();
+    let pixels = chip.framebuffer();
+    assert_eq!(&pixels[..4], &[1, 1, 1, 1]);
+    assert_eq!(&pixels[64..68], &[0, 0, 0, 1]);
+}
+
+#[wasm_bindgen_test]
+fn hold_key() {
+    // Loop until key 5 is held, then draw its font sprite at (0, 0)
+    let mut chip = Chip::new(1);
+    chip.load_rom(&[0x60, 0x05, 0xE0, 0x9E, 0x12, 0x02, 0xF0, 0x29, 0x61, 0x00, 0xD1, 0x15, 0x12, 0x0C]);
+    chip.key_down(0x4);
+    chip.run_frame().unwrap();
+    assert_eq!(chip.framebuffer(), vec![0; 64 * 32]);
+
+    chip.key_down(0x5);
+    chip.run_frame().unwrap();
+    let pixels = chip.framebuffer();
+    assert_eq!(&pixels[..4], &[1, 1, 1, 1]);
+    assert_eq!(&pixels[64..68], &[1, 0, 0, 0]);
+}
+
+#[wasm_bindgen_test]
+fn sound() {
+    // Set the sound timer to 2 frames
+    let mut chip = Chip::new(1);
+    chip.load_rom(&[0x60, 0x02, 0xF0, 0x18, 0x12, 0x04]);
+    assert!(!chip.sound_active());
+    chip.run_frame().unwrap();
+    assert!(chip.sound_active());
+    chip.run_frame().unwrap();
+    assert!(!chip.sound_active());
+}